@@ -0,0 +1,196 @@
+use crate::error::Error;
+use crate::model::purl::encode_qualifier;
+
+use serde::Deserialize;
+use std::io::{BufReader, Read};
+
+/// Source of crates.io, both of git index and sparse index.
+const CRATES_IO_SOURCES: [&str; 2] = [
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
+/// Output of `cargo metadata --format-version 1`.
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Metadata {
+    pub packages: Vec<Package>,
+    pub workspace_members: Vec<String>,
+    /// `None` with `--no-deps`.
+    pub resolve: Option<Resolve>,
+    // other field is umimplemented
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    pub id: String,
+    pub license: Option<String>,
+    /// Relative to the directory of `manifest_path`.
+    pub license_file: Option<String>,
+    /// `None` for path dependencies and workspace members.
+    pub source: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    pub documentation: Option<String>,
+    #[serde(default)]
+    pub targets: Vec<Target>,
+    pub manifest_path: String,
+    // other field is umimplemented
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Target {
+    pub kind: Vec<String>,
+    // other field is umimplemented
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Resolve {
+    pub nodes: Vec<Node>,
+    // other field is umimplemented
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Node {
+    pub id: String,
+    #[serde(default)]
+    pub deps: Vec<NodeDep>,
+    // other field is umimplemented
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct NodeDep {
+    pub pkg: String,
+    #[serde(default)]
+    pub dep_kinds: Vec<DepKind>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct DepKind {
+    /// `None` for normal dependencies, otherwise `dev` or `build`.
+    pub kind: Option<String>,
+}
+
+impl DepKind {
+    pub fn name(&self) -> &str {
+        self.kind.as_deref().unwrap_or("normal")
+    }
+}
+
+impl Package {
+    /// `pkg:cargo` purl. Other registries than crates.io are given by `repository_url`, and git sources by `vcs_url`.
+    /// Path dependencies have no purl.
+    pub fn purl(&self) -> Option<String> {
+        let source = self.source.as_deref()?;
+        let purl = format!("pkg:cargo/{}@{}", self.name, self.version);
+
+        if CRATES_IO_SOURCES.contains(&source) {
+            return Some(purl);
+        }
+
+        if let Some(url) = source
+            .strip_prefix("registry+")
+            .or_else(|| source.strip_prefix("sparse+"))
+        {
+            return Some(format!("{}?repository_url={}", purl, encode_qualifier(url)));
+        }
+
+        // e.g. `git+https://github.com/heriet/foo?branch=main#<commit>` to `git+https://github.com/heriet/foo@<commit>`
+        if source.starts_with("git+") {
+            let (url, commit) = match source.split_once('#') {
+                Some((url, commit)) => (url, Some(commit)),
+                None => (source, None),
+            };
+            let url = url.split_once('?').map(|(url, _)| url).unwrap_or(url);
+            let vcs_url = match commit {
+                Some(commit) => format!("{}@{}", url, commit),
+                None => url.to_string(),
+            };
+            return Some(format!("{}?vcs_url={}", purl, encode_qualifier(&vcs_url)));
+        }
+
+        None
+    }
+
+    /// Whether the package has a binary target, i.e. it is an application rather than a library.
+    pub fn is_application(&self) -> bool {
+        self.targets
+            .iter()
+            .any(|target| target.kind.iter().any(|kind| kind == "bin"))
+    }
+}
+
+pub fn load_metadata<R: Read>(reader: R) -> Result<Metadata, Error> {
+    let metadata = serde_json::from_reader(BufReader::new(reader))?;
+
+    Ok(metadata)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use std::fs::File;
+
+    fn package(name: &str, source: Option<&str>) -> Package {
+        Package {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            id: name.to_string(),
+            license: None,
+            license_file: None,
+            source: source.map(|source| source.to_string()),
+            authors: vec![],
+            repository: None,
+            homepage: None,
+            documentation: None,
+            targets: vec![],
+            manifest_path: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn load_metadata_valid() {
+        let file = File::open("test/cargo/metadata.json").unwrap();
+        let metadata = load_metadata(&file).unwrap();
+
+        assert_eq!(metadata.workspace_members.len(), 2);
+        assert!(metadata.resolve.is_some());
+    }
+
+    #[test]
+    fn cargo_purl() {
+        let crates_io = package(
+            "serde",
+            Some("registry+https://github.com/rust-lang/crates.io-index"),
+        );
+        assert_eq!(crates_io.purl(), Some("pkg:cargo/serde@1.0.0".to_string()));
+
+        let sparse = package("serde", Some("sparse+https://index.crates.io/"));
+        assert_eq!(sparse.purl(), Some("pkg:cargo/serde@1.0.0".to_string()));
+
+        let registry = package("foo", Some("sparse+https://crates.heriet.info/index/"));
+        assert_eq!(
+            registry.purl(),
+            Some(
+                "pkg:cargo/foo@1.0.0?repository_url=https://crates.heriet.info/index/".to_string()
+            )
+        );
+
+        let git = package(
+            "foo",
+            Some("git+https://github.com/heriet/foo?branch=main#0123abc"),
+        );
+        assert_eq!(
+            git.purl(),
+            Some(
+                "pkg:cargo/foo@1.0.0?vcs_url=git%2Bhttps://github.com/heriet/foo%400123abc"
+                    .to_string()
+            )
+        );
+
+        assert_eq!(package("app", None).purl(), None);
+    }
+}
@@ -19,6 +19,7 @@ pub enum SubCommand {
 pub enum OutputFormat {
     Human,
     Json,
+    JsonDocument,
 }
 
 #[derive(Debug, Clone, PartialEq, ArgEnum)]
@@ -27,17 +28,98 @@ pub enum SourceType {
     SpdxTag,
     SpdxJson,
     SpdxYaml,
-    // spdx rdf/xls is unsupported
+    SpdxRdf,
+    SpdxXlsx,
+    Spdx3Json,
     CycloneDxJson,
     CycloneDxXml,
+    CycloneDxProtobuf,
+    CargoMetadata,
+    Npm,
+}
+
+/// Scope of CycloneDX component. Materials without scope are `required`.
+#[derive(Debug, Clone, PartialEq, ArgEnum)]
+pub enum Scope {
+    Required,
+    Optional,
+    Excluded,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Required => "required",
+            Scope::Optional => "optional",
+            Scope::Excluded => "excluded",
+        }
+    }
+}
+
+/// Licenses used as `licenses` of materials.
+#[derive(Debug, Clone, PartialEq, ArgEnum)]
+pub enum EffectiveLicenses {
+    /// Licenses chosen by the loader of each source type
+    Auto,
+    Declared,
+    Concluded,
+    Detected,
+}
+
+impl EffectiveLicenses {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EffectiveLicenses::Auto => "auto",
+            EffectiveLicenses::Declared => "declared",
+            EffectiveLicenses::Concluded => "concluded",
+            EffectiveLicenses::Detected => "detected",
+        }
+    }
+}
+
+/// Sources of SPDX package licenses.
+#[derive(Debug, Clone, PartialEq, ArgEnum)]
+pub enum SpdxLicenseSource {
+    /// `PackageLicenseConcluded`
+    Concluded,
+    /// `PackageLicenseDeclared`
+    Declared,
+    /// `PackageLicenseInfoFromFiles`
+    Files,
 }
 
 #[derive(Debug, Args)]
 pub struct EvaluateArgs {
     #[clap(short = 'p', long = "policy", value_parser, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
     pub policy: Option<PathBuf>,
-    #[clap(short = 'c', long = "curation", value_parser, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
-    pub curation: Option<PathBuf>,
+    #[clap(short = 'c', long = "curation", value_parser, value_name = "FILE", value_hint = clap::ValueHint::FilePath, multiple_occurrences = true)]
+    pub curation: Vec<PathBuf>,
+    #[clap(long = "license-aliases", value_parser, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    pub license_aliases: Option<PathBuf>,
+    #[clap(long = "no-normalize", value_parser)]
+    pub no_normalize: bool,
+    #[clap(long = "license-categories", value_parser, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    pub license_categories: Option<PathBuf>,
+    #[clap(long = "show-categories", value_parser)]
+    pub show_categories: bool,
+    #[clap(long = "project-license", value_parser, value_name = "EXPR")]
+    pub project_license: Option<String>,
+    #[clap(long = "license-compatibility", value_parser, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    pub license_compatibility: Option<PathBuf>,
+    #[clap(long = "effective-licenses", arg_enum, value_name = "SOURCE", default_value = "auto")]
+    pub effective_licenses: EffectiveLicenses,
+    #[clap(long = "spdx-license-source", arg_enum, value_name = "SOURCE", use_value_delimiter = true, multiple_occurrences = true, default_values = &["concluded", "declared", "files"])]
+    pub spdx_license_sources: Vec<SpdxLicenseSource>,
+    #[clap(long = "spdx-files", value_parser)]
+    pub spdx_files: bool,
+    #[clap(long = "follow-bom-references", value_parser)]
+    pub follow_bom_references: bool,
+    #[clap(long = "include-project", value_parser)]
+    pub include_project: bool,
+    #[clap(long = "include-scope", arg_enum, value_name = "SCOPE", use_value_delimiter = true, multiple_occurrences = true, default_values = &["required"])]
+    pub include_scope: Vec<Scope>,
+    #[clap(long = "exclude-scope", arg_enum, value_name = "SCOPE", use_value_delimiter = true, multiple_occurrences = true)]
+    pub exclude_scope: Vec<Scope>,
     #[clap(short = 't', long = "source-type", value_parser)]
     pub source_type: Option<SourceType>,
     #[clap(short = 'o', long = "output", value_parser, default_value = "human")]
@@ -1,59 +1,56 @@
-use crate::cli::{EvaluateArgs, OutputFormat, SourceType};
+use crate::cli::{EffectiveLicenses, EvaluateArgs, OutputFormat, Scope, SourceType};
+use crate::curation::{CurationRecord, DeclarativeCuration};
 use crate::error::Error;
-use crate::model::material::{load_materials, Material};
-
-use ansi_term::Color::{Green, Red, Yellow};
+use crate::license::category::Classifier;
+use crate::license::compatibility::Compatibility;
+use crate::license::LicenseExpression;
+use crate::license::normalize::Normalizer;
+use crate::model::json::find_top_level_value;
+use crate::model::material::{load_materials, load_materials_stdin, LoadOptions, Material};
+use crate::model::result::EvaluateResult;
+use crate::policy::DeclarativePolicy;
+use crate::spdx::is_spdx3_context;
+#[cfg(feature = "python")]
+use crate::python::{curate_materials, evaluate_materials, HattoModule};
+
+use ansi_term::Color::{Blue, Green, Red, Yellow};
 use anyhow::{bail, Result};
-use pyo3::prelude::*;
 use serde::Serialize;
 use serde_json::json;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 
+#[derive(Debug, Serialize)]
+struct EvaluateOutput {
+    pub materials: Vec<MaterialResult>,
+    pub document: EvaluateResult,
+    /// Root component of SBOM.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<Material>,
+    /// Materials skipped by `--include-scope` and `--exclude-scope`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<Material>,
+}
+
 #[derive(Debug, Serialize)]
 struct MaterialResult {
     pub material: Material,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curation: Option<CurationRecord>,
     pub result: EvaluateResult,
 }
 
-#[derive(Debug, Serialize, Clone)]
-#[pyclass]
-struct EvaluateResult {
-    #[pyo3(get)]
-    pub success: bool,
-
-    #[pyo3(get)]
-    pub errors: Vec<String>,
-
-    #[pyo3(get)]
-    pub warnings: Vec<String>,
-}
-
-#[pymethods]
-impl EvaluateResult {
-    #[new]
-    fn new() -> Self {
-        EvaluateResult {
-            success: true,
-            errors: Vec::new(),
-            warnings: Vec::new(),
-        }
-    }
-
-    fn add_error(&mut self, message: String) {
-        self.errors.push(message);
-        self.success = false;
-    }
-
-    fn add_warning(&mut self, message: String) {
-        self.warnings.push(message);
-    }
-}
+/// Source path to read stdin, e.g. `cargo metadata --format-version 1 | hatto evaluate -`.
+const STDIN: &str = "-";
 
+#[cfg(feature = "python")]
 const DEFAULT_POLICY: &str = r#"
 #!/usr/bin/python
 
+import hatto
+
 allowed_licenses = [
     "Apache-2.0",
     "MIT",
@@ -65,74 +62,222 @@ def evaluate(material, result):
     for license in material.licenses:
         if license not in allowed_licenses:
            result.add_error(f"{license} is not allowed")
-"#;
 
-const DEFAULT_CURATION: &str = r#"
-#!/usr/bin/python
+    expression = material.license_expression
+    if hatto.project_license is not None and expression is not None:
+        if not hatto.is_compatible(expression, hatto.project_license):
+           result.add_error(f"{expression} is not compatible with project license {hatto.project_license}")
+"#;
 
-def curate_material(material):
-    pass
+/// Used instead of `DEFAULT_POLICY` when hatto is built without python.
+#[cfg(not(feature = "python"))]
+const DEFAULT_DECLARATIVE_POLICY: &str = r#"
+allowed = [
+    "Apache-2.0",
+    "MIT",
+    "BSD-3-Clause",
+    "Unlicense",
+]
 "#;
 
-pub fn exec(args: &EvaluateArgs) -> Result<()> {
-    let policy_py = load_policy(args)?;
-    let curation_py = load_curation(args)?;
+enum Policy {
+    #[cfg(feature = "python")]
+    Python(String),
+    Declarative(DeclarativePolicy),
+}
 
-    let source_file = match File::open(&args.source) {
-        Err(err) => bail!(Error::Io(err)),
-        Ok(file) => file,
+enum Curation {
+    #[cfg(feature = "python")]
+    Python(String),
+    Declarative(DeclarativeCuration),
+}
+
+pub fn exec(args: &EvaluateArgs) -> Result<()> {
+    let policy = load_policy(args)?;
+    let normalizer = match args.no_normalize {
+        true => None,
+        false => Some(load_normalizer(args)?),
+    };
+    let project_license = match &args.project_license {
+        Some(expression) => Some(parse_project_license(expression, normalizer.as_ref())?),
+        None => None,
+    };
+    let curations = args
+        .curation
+        .iter()
+        .map(|curation_path| load_curation(curation_path))
+        .collect::<Result<Vec<Curation>, Error>>()?;
+
+    let materials: Vec<Material> = match args.source == Path::new(STDIN) {
+        // only `cargo metadata` is piped in practice, so that it is the default of stdin
+        true => load_materials_stdin(args.source_type.as_ref().unwrap_or(&SourceType::CargoMetadata))?,
+        false => {
+            let source_file = match File::open(&args.source) {
+                Err(err) => bail!(Error::Io(err)),
+                Ok(file) => file,
+            };
+
+            let source_type = match &args.source_type {
+                Some(s) => s.clone(),
+                None => detect_source_type(&args.source),
+            };
+
+            let load_options = LoadOptions {
+                spdx_license_sources: args.spdx_license_sources.clone(),
+                spdx_files: args.spdx_files,
+                follow_bom_references: args.follow_bom_references,
+                source_path: Some(args.source.clone()),
+            };
+            load_materials(&source_file, &source_type, &load_options)?
+        }
     };
 
-    let source_type = match &args.source_type {
-        Some(s) => s.clone(),
-        None => detect_source_type(&args.source),
+    // dependency graph refers to materials by id, which is shown by name
+    let names: HashMap<String, String> = materials
+        .iter()
+        .filter_map(|material| material.id.clone().map(|id| (id, material.name.clone())))
+        .collect();
+
+    // the project is what is being evaluated, so it is not a material to evaluate by default. A workspace has many
+    // projects, and the first one is `hatto.project`
+    let projects: Vec<Material> = materials.iter().filter(|material| material.is_project).cloned().collect();
+    let project = projects.first().cloned();
+    let materials = match args.include_project {
+        true => materials,
+        false => materials.into_iter().filter(|material| !material.is_project).collect(),
     };
 
-    let materials: Vec<Material> = load_materials(&source_file, &source_type)?;
-    let mut results: Vec<MaterialResult> = Vec::new();
-    let mut success = true;
+    // out of scope materials are not shipped, so they are neither curated nor evaluated
+    let (mut materials, skipped) = filter_scope(materials, &args.include_scope, &args.exclude_scope);
 
-    let py_result: PyResult<()> = Python::with_gil(|py| {
-        let policy_module = PyModule::from_code(py, &policy_py, "", "")?;
-        let evaluate: Py<PyAny> = policy_module.getattr("evaluate")?.into();
+    // what the source reported, before any change by hatto
+    let loaded = materials.clone();
 
-        let curation_module = PyModule::from_code(py, &curation_py, "", "")?;
-        let curate: Py<PyAny> = curation_module.getattr("curate_material")?.into();
+    for material in &mut materials {
+        material.select_licenses(&args.effective_licenses);
+    }
 
-        for material in materials {
-            // call curate
-            let py_material = Py::new(py, material.clone())?;
-            curate.call1(py, (&py_material,))?;
-            let curated_material: Material = py_material.extract(py)?;
+    if let Some(normalizer) = &normalizer {
+        for material in &mut materials {
+            normalizer.normalize_material(material);
+        }
+    }
+
+    let classifier = load_classifier(args)?;
+    for material in &mut materials {
+        material.license_categories = classifier.categories(&material.licenses);
+    }
 
-            // call evaluate
-            let py_curated_material = Py::new(py, curated_material.clone())?;
-            let py_evaluate_result = Py::new(py, EvaluateResult::new())?;
-            evaluate.call1(py, (&py_curated_material, &py_evaluate_result))?;
-            let evaluate_result: EvaluateResult = py_evaluate_result.extract(py)?;
+    let compatibility = load_compatibility(args, &classifier)?;
 
-            if args.output_format == OutputFormat::Human {
-                print_evaluate_result_for_human(&curated_material, &evaluate_result);
+    #[cfg(feature = "python")]
+    let hatto_module = HattoModule {
+        classifier: classifier.clone(),
+        compatibility: compatibility.clone(),
+        project_license: project_license.clone(),
+        project: project.clone(),
+    };
+
+    let uncurated = materials.clone();
+    for curation in &curations {
+        materials = match curation {
+            Curation::Declarative(declarative_curation) => {
+                for material in &mut materials {
+                    declarative_curation.curate_material(material);
+                }
+                materials
             }
+            #[cfg(feature = "python")]
+            Curation::Python(curation_py) => curate_materials(curation_py, materials, &hatto_module)?,
+        };
+    }
+    for material in &mut materials {
+        material.license_categories = classifier.categories(&material.licenses);
+    }
+
+    let (mut evaluate_results, document_result) = match &policy {
+        Policy::Declarative(declarative_policy) => (
+            materials
+                .iter()
+                .map(|material| {
+                    declarative_policy.evaluate(material, project_license.as_ref().map(|p| (p, &compatibility)))
+                })
+                .collect::<Vec<_>>(),
+            EvaluateResult::new(),
+        ),
+        #[cfg(feature = "python")]
+        Policy::Python(policy_py) => evaluate_materials(policy_py, &materials, &hatto_module)?,
+    };
 
-            if !evaluate_result.success {
-                success = false;
+    // otherwise a material without licenses (e.g. all NOASSERTION in SPDX) passes any policy
+    for (material, result) in materials.iter().zip(evaluate_results.iter_mut()) {
+        if material.licenses.is_empty() {
+            match args.effective_licenses {
+                EffectiveLicenses::Auto => result.add_error("no licenses".to_string()),
+                ref source => result.add_error(format!("no {} licenses", source.as_str())),
             }
+        }
+    }
+
+    let mut results: Vec<MaterialResult> = Vec::new();
+    let mut success = true;
+
+    if args.output_format == OutputFormat::Human {
+        for project in &projects {
+            println!(
+                "{} {} {}",
+                Blue.paint("PROJECT"),
+                project.name,
+                project.version.clone().unwrap_or_default()
+            );
+        }
+    }
+
+    for (((material, original), uncurated), evaluate_result) in
+        materials.into_iter().zip(loaded).zip(uncurated).zip(evaluate_results)
+    {
+        let curation = CurationRecord::new(original, &uncurated, &material);
 
-            results.push(MaterialResult {
-                material: curated_material,
-                result: evaluate_result,
-            });
+        if args.output_format == OutputFormat::Human {
+            print_evaluate_result_for_human(&material, &curation, &evaluate_result, &names, args.show_categories);
         }
-        Ok(())
-    });
 
-    if let Err(err) = py_result {
-        bail!(err)
+        if !evaluate_result.success {
+            success = false;
+        }
+
+        results.push(MaterialResult {
+            material,
+            curation,
+            result: evaluate_result,
+        });
     }
 
-    if args.output_format == OutputFormat::Json {
-        println!("{}", json!(results));
+    if args.output_format == OutputFormat::Human {
+        print_document_result_for_human(&document_result);
+        print_skipped_materials_for_human(&skipped);
+    }
+
+    if !document_result.success {
+        success = false;
+    }
+
+    match args.output_format {
+        OutputFormat::Human => {}
+        OutputFormat::Json => {
+            // the list of materials has no place for the result of the document
+            print_document_messages_to_stderr(&document_result);
+            println!("{}", json!(results));
+        }
+        OutputFormat::JsonDocument => {
+            let output = EvaluateOutput {
+                materials: results,
+                document: document_result,
+                project,
+                skipped,
+            };
+            println!("{}", json!(output));
+        }
     }
 
     match success {
@@ -141,8 +286,25 @@ pub fn exec(args: &EvaluateArgs) -> Result<()> {
     }
 }
 
-fn print_evaluate_result_for_human(material: &Material, result: &EvaluateResult) {
-    let version = material.version.clone().unwrap_or_else(|| "".to_string());
+fn print_evaluate_result_for_human(
+    material: &Material,
+    curation: &Option<CurationRecord>,
+    result: &EvaluateResult,
+    names: &HashMap<String, String>,
+    show_categories: bool,
+) {
+    let version = material.version.clone().unwrap_or_default();
+    let licenses = match show_categories {
+        true => material
+            .licenses
+            .iter()
+            .map(|license| {
+                let category = material.license_categories.get(license).map_or("unknown", |c| c.as_str());
+                format!("{} ({})", license, category)
+            })
+            .collect::<Vec<_>>(),
+        false => material.licenses.clone(),
+    };
 
     if result.success {
         println!(
@@ -150,7 +312,7 @@ fn print_evaluate_result_for_human(material: &Material, result: &EvaluateResult)
             Green.paint("OK"),
             material.name,
             version,
-            material.licenses,
+            licenses,
             material.annotations
         );
     } else {
@@ -159,13 +321,55 @@ fn print_evaluate_result_for_human(material: &Material, result: &EvaluateResult)
             Red.paint("NG"),
             material.name,
             version,
-            material.licenses,
+            licenses,
             material.annotations
         );
     }
 
     let indent = "  "; // two spaces
 
+    if let Some(curation) = curation {
+        for change in &curation.changes {
+            println!("{}{} {}", indent, Blue.paint("CURATED"), change);
+        }
+
+        for reason in &curation.reasons {
+            println!("{}{} {}", indent, Blue.paint("REASON"), reason);
+        }
+    }
+
+    for message in &result.errors {
+        println!("{}{} {}", indent, Red.paint("ERROR"), message);
+    }
+
+    for message in &result.warnings {
+        println!("{}{} {}", indent, Yellow.paint("WARNING"), message);
+    }
+
+    // show how the material is introduced to find the dependency to fix
+    if !result.success {
+        for path in material.paths_from_root.iter().filter(|path| path.len() > 1) {
+            let path = path
+                .iter()
+                .map(|id| names.get(id).unwrap_or(id).as_str())
+                .collect::<Vec<_>>();
+            println!("{}{} {}", indent, Red.paint("PATH"), path.join(" -> "));
+        }
+    }
+}
+
+fn print_document_result_for_human(result: &EvaluateResult) {
+    if result.errors.is_empty() && result.warnings.is_empty() {
+        return;
+    }
+
+    match result.success {
+        true => println!("{} (document)", Green.paint("OK")),
+        false => println!("{} (document)", Red.paint("NG")),
+    }
+
+    let indent = "  "; // two spaces
+
     for message in &result.errors {
         println!("{}{} {}", indent, Red.paint("ERROR"), message);
     }
@@ -175,6 +379,46 @@ fn print_evaluate_result_for_human(material: &Material, result: &EvaluateResult)
     }
 }
 
+fn print_document_messages_to_stderr(result: &EvaluateResult) {
+    for message in &result.errors {
+        eprintln!("{} (document) {}", Red.paint("ERROR"), message);
+    }
+
+    for message in &result.warnings {
+        eprintln!("{} (document) {}", Yellow.paint("WARNING"), message);
+    }
+}
+
+fn print_skipped_materials_for_human(skipped: &[Material]) {
+    if skipped.is_empty() {
+        return;
+    }
+
+    println!("{} {} materials by scope", Blue.paint("SKIPPED"), skipped.len());
+
+    let indent = "  "; // two spaces
+
+    for material in skipped {
+        println!(
+            "{}{} {} scope:{}",
+            indent,
+            material.name,
+            material.version.clone().unwrap_or_default(),
+            material.scope.as_deref().unwrap_or(Scope::Required.as_str())
+        );
+    }
+}
+
+/// Split materials into the materials in scope and the skipped materials. Materials without scope are `required`.
+fn filter_scope(materials: Vec<Material>, include: &[Scope], exclude: &[Scope]) -> (Vec<Material>, Vec<Material>) {
+    materials.into_iter().partition(|material| {
+        let scope = material.scope.as_deref().unwrap_or(Scope::Required.as_str());
+        let is_scope = |s: &Scope| s.as_str() == scope;
+
+        include.iter().any(is_scope) && !exclude.iter().any(is_scope)
+    })
+}
+
 fn detect_source_type<P: AsRef<Path>>(path: &P) -> SourceType {
     let p_ref = path.as_ref();
     let path_str = p_ref.to_str().unwrap();
@@ -183,23 +427,53 @@ fn detect_source_type<P: AsRef<Path>>(path: &P) -> SourceType {
         return SourceType::Tsv;
     } else if path_str.ends_with(".spdx") {
         return SourceType::SpdxTag;
+    } else if path_str.ends_with(".spdx3.json") {
+        return SourceType::Spdx3Json;
     } else if path_str.ends_with(".spdx.json") {
-        return SourceType::SpdxJson;
+        return match detect_json_source_type(p_ref) {
+            Some(SourceType::Spdx3Json) => SourceType::Spdx3Json,
+            _ => SourceType::SpdxJson,
+        };
     } else if path_str.ends_with(".spdx.yml") || path_str.ends_with(".spdx.yaml") {
         return SourceType::SpdxYaml;
+    } else if path_str.ends_with(".spdx.rdf") || path_str.ends_with(".spdx.xml") || path_str.ends_with(".spdx.rdf.xml") {
+        return SourceType::SpdxRdf;
+    } else if path_str.ends_with(".spdx.xlsx") {
+        return SourceType::SpdxXlsx;
     } else if p_ref.ends_with("bom.json") || path_str.ends_with(".cdx.json") {
         return SourceType::CycloneDxJson;
+    } else if p_ref.ends_with("package-lock.json") || p_ref.ends_with("npm-shrinkwrap.json") {
+        return SourceType::Npm;
     } else if p_ref.ends_with("bom.xml") || path_str.ends_with(".cdx.xml") {
         return SourceType::CycloneDxXml;
+    } else if path_str.ends_with(".cdx.bin") {
+        return SourceType::CycloneDxProtobuf;
+    } else if path_str.ends_with(".json") || path_str.ends_with(".jsonld") {
+        if let Some(source_type) = detect_json_source_type(p_ref) {
+            return source_type;
+        }
     }
 
     SourceType::Tsv
 }
 
-fn load_policy(args: &EvaluateArgs) -> Result<String, Error> {
+/// SPDX 3 JSON-LD and `cargo metadata` have no extension of their own, so they are detected by `@context` and
+/// `workspace_members` in one pass, which stops at the first of them.
+fn detect_json_source_type(path: &Path) -> Option<SourceType> {
+    let file = File::open(path).ok()?;
+    match find_top_level_value(file, &["@context", "workspace_members"])? {
+        (key, context) if key == "@context" => is_spdx3_context(&context).then_some(SourceType::Spdx3Json),
+        (_, members) => members.is_array().then_some(SourceType::CargoMetadata),
+    }
+}
+
+fn load_policy(args: &EvaluateArgs) -> Result<Policy, Error> {
     let policy_path = match &args.policy {
         Some(v) => v,
-        None => return Ok(DEFAULT_POLICY.to_string()),
+        #[cfg(feature = "python")]
+        None => return Ok(Policy::Python(DEFAULT_POLICY.to_string())),
+        #[cfg(not(feature = "python"))]
+        None => return Ok(Policy::Declarative(DeclarativePolicy::from_toml(DEFAULT_DECLARATIVE_POLICY)?)),
     };
 
     let mut policy_file = match File::open(policy_path) {
@@ -208,30 +482,95 @@ fn load_policy(args: &EvaluateArgs) -> Result<String, Error> {
     };
 
     let mut content = String::new();
-    match policy_file.read_to_string(&mut content) {
-        Err(err) => return Err(Error::Io(err)),
-        Ok(_) => return Ok(content),
-    };
+    if let Err(err) = policy_file.read_to_string(&mut content) {
+        return Err(Error::Io(err));
+    }
+
+    match policy_path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => Ok(Policy::Declarative(DeclarativePolicy::from_toml(&content)?)),
+        Some("yml") | Some("yaml") => Ok(Policy::Declarative(DeclarativePolicy::from_yaml(&content)?)),
+        #[cfg(feature = "python")]
+        _ => Ok(Policy::Python(content)),
+        #[cfg(not(feature = "python"))]
+        _ => Err(Error::Failure(
+            "python policy is unsupported because hatto is built without python feature".to_string(),
+        )),
+    }
 }
 
-fn load_curation(args: &EvaluateArgs) -> Result<String, Error> {
-    let curation_path = match &args.curation {
-        Some(v) => v,
-        None => return Ok(DEFAULT_CURATION.to_string()),
-    };
+fn load_compatibility(args: &EvaluateArgs, classifier: &Classifier) -> Result<Compatibility, Error> {
+    let mut compatibility = Compatibility::new(classifier.clone())?;
 
+    if let Some(compatibility_path) = &args.license_compatibility {
+        let compatibility_file = match File::open(compatibility_path) {
+            Err(err) => return Err(Error::Io(err)),
+            Ok(file) => file,
+        };
+        compatibility.load(compatibility_file)?;
+    }
+
+    Ok(compatibility)
+}
+
+/// The project license is normalized in the same way as materials, so that deprecated `GPL-2.0` is checked as
+/// `GPL-2.0-only` against the compatibility table.
+fn parse_project_license(expression: &str, normalizer: Option<&Normalizer>) -> Result<LicenseExpression, Error> {
+    match normalizer {
+        Some(normalizer) => normalizer.parse_expression(expression),
+        None => LicenseExpression::parse(expression),
+    }
+}
+
+fn load_normalizer(args: &EvaluateArgs) -> Result<Normalizer, Error> {
+    let mut normalizer = Normalizer::new()?;
+
+    if let Some(aliases_path) = &args.license_aliases {
+        let aliases_file = match File::open(aliases_path) {
+            Err(err) => return Err(Error::Io(err)),
+            Ok(file) => file,
+        };
+        normalizer.load_user_aliases(aliases_file)?;
+    }
+
+    Ok(normalizer)
+}
+
+fn load_classifier(args: &EvaluateArgs) -> Result<Classifier, Error> {
+    let mut classifier = Classifier::new()?;
+
+    if let Some(categories_path) = &args.license_categories {
+        let categories_file = match File::open(categories_path) {
+            Err(err) => return Err(Error::Io(err)),
+            Ok(file) => file,
+        };
+        classifier.load(categories_file)?;
+    }
+
+    Ok(classifier)
+}
+
+fn load_curation(curation_path: &Path) -> Result<Curation, Error> {
     let mut curation_file = match File::open(curation_path) {
         Err(err) => return Err(Error::Io(err)),
         Ok(file) => file,
     };
 
     let mut content = String::new();
-    match curation_file.read_to_string(&mut content) {
-        Err(err) => return Err(Error::Io(err)),
-        Ok(_) => return Ok(content),
-    };
-}
+    if let Err(err) = curation_file.read_to_string(&mut content) {
+        return Err(Error::Io(err));
+    }
 
+    match curation_path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => Ok(Curation::Declarative(DeclarativeCuration::from_toml(&content)?)),
+        Some("yml") | Some("yaml") => Ok(Curation::Declarative(DeclarativeCuration::from_yaml(&content)?)),
+        #[cfg(feature = "python")]
+        _ => Ok(Curation::Python(content)),
+        #[cfg(not(feature = "python"))]
+        _ => Err(Error::Failure(
+            "python curation is unsupported because hatto is built without python feature".to_string(),
+        )),
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -254,6 +593,25 @@ mod tests {
         let path_spdx_yaml = Path::new("./foo/bar.spdx.yaml");
         assert_eq!(detect_source_type(&path_spdx_yaml), SourceType::SpdxYaml);
 
+        let path_spdx_rdf = Path::new("./foo/bar.spdx.rdf");
+        assert_eq!(detect_source_type(&path_spdx_rdf), SourceType::SpdxRdf);
+
+        let path_spdx_xml = Path::new("./foo/bar.spdx.xml");
+        assert_eq!(detect_source_type(&path_spdx_xml), SourceType::SpdxRdf);
+
+        let path_spdx_xlsx = Path::new("./foo/bar.spdx.xlsx");
+        assert_eq!(detect_source_type(&path_spdx_xlsx), SourceType::SpdxXlsx);
+
+        let path_spdx3_json = Path::new("./foo/bar.spdx3.json");
+        assert_eq!(detect_source_type(&path_spdx3_json), SourceType::Spdx3Json);
+
+        // detected by @context
+        let path_spdx3_json = Path::new("test/spdx/basic.spdx3.json");
+        assert_eq!(detect_source_type(&path_spdx3_json), SourceType::Spdx3Json);
+
+        let path_spdx2_json = Path::new("test/spdx/basic.spdx.json");
+        assert_eq!(detect_source_type(&path_spdx2_json), SourceType::SpdxJson);
+
         let path_cdx_json = Path::new("./foo/bar.cdx.json");
         assert_eq!(
             detect_source_type(&path_cdx_json),
@@ -271,5 +629,61 @@ mod tests {
 
         let path_bom_xml = Path::new("./foo/bom.xml");
         assert_eq!(detect_source_type(&path_bom_xml), SourceType::CycloneDxXml);
+
+        let path_cdx_bin = Path::new("./foo/bar.cdx.bin");
+        assert_eq!(detect_source_type(&path_cdx_bin), SourceType::CycloneDxProtobuf);
+
+        let path_package_lock = Path::new("./foo/package-lock.json");
+        assert_eq!(detect_source_type(&path_package_lock), SourceType::Npm);
+
+        // detected by workspace_members
+        let path_cargo_metadata = Path::new("test/cargo/metadata.json");
+        assert_eq!(detect_source_type(&path_cargo_metadata), SourceType::CargoMetadata);
+    }
+
+    #[test]
+    fn test_filter_scope() {
+        let file = File::open("test/cyclonedx/full.cdx.json").unwrap();
+        let materials = load_materials(&file, &SourceType::CycloneDxJson, &LoadOptions::default()).unwrap();
+        // the project is removed before filtering scope
+        let materials = materials.into_iter().filter(|m| !m.is_project).collect::<Vec<_>>();
+        let names = |materials: &[Material]| materials.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+
+        let (materials_in_scope, skipped) = filter_scope(materials.clone(), &[Scope::Required], &[]);
+        assert_eq!(names(&materials_in_scope), vec!["foo"]);
+        assert_eq!(names(&skipped), vec!["foo-core", "bar"]);
+
+        let (materials_in_scope, skipped) =
+            filter_scope(materials.clone(), &[Scope::Required, Scope::Optional], &[]);
+        assert_eq!(names(&materials_in_scope), vec!["foo", "foo-core"]);
+        assert_eq!(names(&skipped), vec!["bar"]);
+
+        let (materials_in_scope, skipped) = filter_scope(
+            materials,
+            &[Scope::Required, Scope::Optional, Scope::Excluded],
+            &[Scope::Optional],
+        );
+        assert_eq!(names(&materials_in_scope), vec!["foo", "bar"]);
+        assert_eq!(names(&skipped), vec!["foo-core"]);
+    }
+
+    #[test]
+    fn test_parse_project_license() {
+        let normalizer = Normalizer::new().unwrap();
+        let compatibility = Compatibility::new(Classifier::new().unwrap()).unwrap();
+        let apache = LicenseExpression::parse("Apache-2.0").unwrap();
+
+        // deprecated GPL-2.0 must hit the Apache-2.0 GPL-2.0-only entry
+        let project_license = parse_project_license("GPL-2.0", Some(&normalizer)).unwrap();
+        assert_eq!(project_license.to_string(), "GPL-2.0-only");
+        assert!(!compatibility.is_compatible(&apache, &project_license));
+
+        let project_license = parse_project_license("Apache 2.0", Some(&normalizer)).unwrap();
+        assert_eq!(project_license.to_string(), "Apache-2.0");
+
+        // --no-normalize
+        let project_license = parse_project_license("GPL-2.0", None).unwrap();
+        assert_eq!(project_license.to_string(), "GPL-2.0");
+        assert!(parse_project_license("Apache 2.0", None).is_err());
     }
 }
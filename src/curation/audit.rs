@@ -0,0 +1,166 @@
+use crate::model::material::Material;
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A field of material changed by curation. `None` means the field was not set.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.field,
+            self.before.as_deref().unwrap_or("(none)"),
+            self.after.as_deref().unwrap_or("(none)")
+        )
+    }
+}
+
+/// What curations changed on a material and why.
+#[derive(Debug, Clone, Serialize)]
+pub struct CurationRecord {
+    /// Material as loaded from the source.
+    pub original: Material,
+    /// Changes by `--effective-licenses` and normalization before curations.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub normalization: Vec<FieldChange>,
+    pub changes: Vec<FieldChange>,
+    pub reasons: Vec<String>,
+}
+
+impl CurationRecord {
+    /// `uncurated` is the material passed to curations, i.e. `original` after `--effective-licenses` and
+    /// normalization. Returns `None` if curations neither changed the material nor gave any reason.
+    pub fn new(original: Material, uncurated: &Material, curated: &Material) -> Option<Self> {
+        let changes = diff_materials(uncurated, curated);
+        if changes.is_empty() && curated.curation_reasons.is_empty() {
+            return None;
+        }
+
+        Some(CurationRecord {
+            normalization: diff_materials(&original, uncurated),
+            original,
+            changes,
+            reasons: curated.curation_reasons.clone(),
+        })
+    }
+}
+
+fn diff_materials(before: &Material, after: &Material) -> Vec<FieldChange> {
+    let mut changes: Vec<FieldChange> = Vec::new();
+
+    let mut push = |field: &str, before: Option<String>, after: Option<String>| {
+        if before != after {
+            changes.push(FieldChange {
+                field: field.to_string(),
+                before,
+                after,
+            });
+        }
+    };
+
+    push("name", Some(before.name.clone()), Some(after.name.clone()));
+    push("version", before.version.clone(), after.version.clone());
+    push("purl", before.purl.clone(), after.purl.clone());
+    push("group", before.group.clone(), after.group.clone());
+    push("supplier", before.supplier.clone(), after.supplier.clone());
+    push("type", before.material_type.clone(), after.material_type.clone());
+    push("id", before.id.clone(), after.id.clone());
+    push("scope", before.scope.clone(), after.scope.clone());
+    push("cpe", before.cpe.clone(), after.cpe.clone());
+    push("author", before.author.clone(), after.author.clone());
+    push("publisher", before.publisher.clone(), after.publisher.clone());
+    push("copyright", before.copyright.clone(), after.copyright.clone());
+    push("licenses", licenses_string(before), licenses_string(after));
+
+    let keys = before
+        .annotations
+        .keys()
+        .chain(after.annotations.keys())
+        .collect::<BTreeSet<_>>();
+    for key in keys {
+        push(
+            &format!("annotations.{}", key),
+            before.annotations.get(key).cloned(),
+            after.annotations.get(key).cloned(),
+        );
+    }
+
+    changes
+}
+
+fn licenses_string(material: &Material) -> Option<String> {
+    match &material.license_expression {
+        Some(expression) => Some(expression.to_string()),
+        None if material.licenses.is_empty() => None,
+        None => Some(material.licenses.join(",")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_changes() {
+        let mut original = Material {
+            name: "bar".to_string(),
+            version: Some("1.1.2".to_string()),
+            ..Default::default()
+        };
+        original.set_license_strings(vec!["UNKNOWN".to_string()]);
+
+        let mut curated = original.clone();
+        curated.set_license_strings(vec!["BSD-3-Clause".to_string()]);
+        curated
+            .annotations
+            .insert("usage".to_string(), "service".to_string());
+        curated.curation_reasons.push("checked upstream LICENSE".to_string());
+
+        let record = CurationRecord::new(original.clone(), &original, &curated).unwrap();
+        let changes = record.changes.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                "licenses: UNKNOWN -> BSD-3-Clause",
+                "annotations.usage: (none) -> service"
+            ]
+        );
+        assert_eq!(record.reasons, vec!["checked upstream LICENSE"]);
+        assert_eq!(record.original.licenses, vec!["UNKNOWN"]);
+        assert!(record.normalization.is_empty());
+
+        assert!(CurationRecord::new(original.clone(), &original, &original).is_none());
+    }
+
+    #[test]
+    fn record_normalization_separately() {
+        let mut original = Material {
+            name: "foo".to_string(),
+            ..Default::default()
+        };
+        original.set_license_strings(vec!["Apache 2.0".to_string()]);
+
+        let mut normalized = original.clone();
+        normalized.set_license_strings(vec!["Apache-2.0".to_string()]);
+
+        let mut curated = normalized.clone();
+        curated.set_license_strings(vec!["MIT".to_string()]);
+
+        let record = CurationRecord::new(original.clone(), &normalized, &curated).unwrap();
+        assert_eq!(record.original.licenses, vec!["Apache 2.0"]);
+        assert_eq!(record.normalization[0].to_string(), "licenses: Apache 2.0 -> Apache-2.0");
+        assert_eq!(record.changes[0].to_string(), "licenses: Apache-2.0 -> MIT");
+
+        // normalization only is not a curation
+        assert!(CurationRecord::new(original, &normalized, &normalized).is_none());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::error::Error;
+use crate::license::LicenseExpression;
+use crate::model::material::Material;
+use crate::model::purl::PackageUrl;
+
+use semver::{Version, VersionReq};
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Curation written in TOML or YAML, applied without Python.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DeclarativeCuration {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+/// Changes applied to materials that match all of the conditions.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    #[serde(rename = "match", default)]
+    pub conditions: Conditions,
+
+    /// Replace all licenses by the licenses. Each license can be SPDX license expression.
+    pub licenses: Option<Vec<String>>,
+
+    /// Replace a license by a license expression, in the order written. A replacement sees the replacements before.
+    #[serde(default, deserialize_with = "deserialize_ordered_pairs")]
+    pub replace_licenses: Vec<(String, String)>,
+
+    #[serde(default)]
+    pub annotations: HashMap<String, String>,
+
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Conditions {
+    pub name: Option<String>,
+
+    /// Exact version or semver version requirement (e.g. `>=1.0, <2.0`).
+    pub version: Option<String>,
+
+    /// purl without version (e.g. `pkg:npm/foo`) matches any version.
+    pub purl: Option<String>,
+
+    /// Current licenses in any order.
+    pub licenses: Option<Vec<String>>,
+}
+
+impl Conditions {
+    fn matches(&self, material: &Material) -> bool {
+        if let Some(name) = &self.name {
+            if *name != material.name {
+                return false;
+            }
+        }
+
+        if let Some(version) = &self.version {
+            match &material.version {
+                Some(material_version) if matches_version(version, material_version) => (),
+                _ => return false,
+            }
+        }
+
+        if let Some(purl) = &self.purl {
+            match &material.purl {
+                Some(material_purl) if matches_purl(purl, material_purl) => (),
+                _ => return false,
+            }
+        }
+
+        if let Some(licenses) = &self.licenses {
+            let mut expected = licenses.iter().map(|l| l.as_str()).collect::<Vec<_>>();
+            let mut actual = material.licenses.iter().map(|l| l.as_str()).collect::<Vec<_>>();
+            expected.sort_unstable();
+            actual.sort_unstable();
+            if expected != actual {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl Rule {
+    fn apply(&self, material: &mut Material) {
+        if let Some(licenses) = &self.licenses {
+            material.set_license_strings(licenses.clone());
+        }
+
+        for (from, to) in &self.replace_licenses {
+            replace_license(material, from, to);
+        }
+
+        material.annotations.extend(self.annotations.clone());
+
+        if let Some(comment) = &self.comment {
+            material.curation_reasons.push(comment.trim_end().to_string());
+        }
+    }
+}
+
+impl DeclarativeCuration {
+    pub fn from_toml(content: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn from_yaml(content: &str) -> Result<Self, Error> {
+        Ok(serde_yaml::from_str(content)?)
+    }
+
+    /// Apply every matched rule in order. Conditions of a rule see the changes of the rules before.
+    pub fn curate_material(&self, material: &mut Material) {
+        for rule in &self.rules {
+            if rule.conditions.matches(material) {
+                rule.apply(material);
+            }
+        }
+    }
+}
+
+/// Map as key-value pairs in the order written, which `HashMap` loses.
+fn deserialize_ordered_pairs<'de, D>(deserializer: D) -> Result<Vec<(String, String)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct OrderedPairs;
+
+    impl<'de> Visitor<'de> for OrderedPairs {
+        type Value = Vec<(String, String)>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map of strings")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut pairs = Vec::new();
+            while let Some(pair) = map.next_entry()? {
+                pairs.push(pair);
+            }
+            Ok(pairs)
+        }
+    }
+
+    deserializer.deserialize_map(OrderedPairs)
+}
+
+fn matches_version(condition: &str, version: &str) -> bool {
+    if condition == version {
+        return true;
+    }
+
+    // plain version is exact match, not `^` requirement
+    if Version::parse(condition).is_ok() {
+        return false;
+    }
+
+    match (VersionReq::parse(condition), Version::parse(version)) {
+        (Ok(req), Ok(version)) => req.matches(&version),
+        _ => false,
+    }
+}
+
+fn matches_purl(condition: &str, purl: &str) -> bool {
+    match (PackageUrl::parse(condition), PackageUrl::parse(purl)) {
+        (Ok(condition), Ok(purl)) => {
+            condition.package_type == purl.package_type
+                && condition.namespace == purl.namespace
+                && condition.name == purl.name
+                && (condition.version.is_none() || condition.version == purl.version)
+        }
+        _ => condition == purl,
+    }
+}
+
+fn replace_license(material: &mut Material, from: &str, to: &str) {
+    match &material.license_expression {
+        Some(expression) => {
+            let replacement = match LicenseExpression::parse(to) {
+                Ok(replacement) => replacement,
+                Err(_) => return replace_license_string(material, from, to),
+            };
+            let replaced = expression.map_licenses(&|license| match license.to_string() == from {
+                true => Some(replacement.clone()),
+                false => None,
+            });
+            material.set_expression(replaced);
+        }
+        None => replace_license_string(material, from, to),
+    }
+}
+
+fn replace_license_string(material: &mut Material, from: &str, to: &str) {
+    let licenses = material
+        .licenses
+        .iter()
+        .map(|license| match license == from {
+            true => to.to_string(),
+            false => license.clone(),
+        })
+        .collect();
+    material.set_license_strings(licenses);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn material(name: &str, version: &str, licenses: &[&str]) -> Material {
+        let mut material = Material {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+            purl: Some(format!("pkg:npm/{}@{}", name, version)),
+            ..Default::default()
+        };
+        material.set_license_strings(licenses.iter().map(|l| l.to_string()).collect());
+        material
+    }
+
+    const CURATION: &str = r#"
+[[rules]]
+match = { name = "foo", version = ">=1.0.0, <2.0.0" }
+licenses = ["MIT OR Apache-2.0"]
+comment = "checked upstream LICENSE"
+
+[[rules]]
+match = { purl = "pkg:npm/bar" }
+annotations = { usage = "service" }
+
+[[rules]]
+match = { licenses = ["UNKNOWN", "MIT"] }
+replace_licenses = { UNKNOWN = "BSD-3-Clause" }
+"#;
+
+    #[test]
+    fn curate_by_name_and_version() {
+        let curation = DeclarativeCuration::from_toml(CURATION).unwrap();
+
+        let mut foo = material("foo", "1.2.0", &["UNKNOWN"]);
+        curation.curate_material(&mut foo);
+        assert_eq!(foo.licenses, vec!["MIT", "Apache-2.0"]);
+        assert_eq!(
+            foo.license_expression.unwrap().to_string(),
+            "MIT OR Apache-2.0"
+        );
+        assert_eq!(foo.curation_reasons, vec!["checked upstream LICENSE"]);
+
+        let mut foo = material("foo", "2.0.0", &["UNKNOWN"]);
+        curation.curate_material(&mut foo);
+        assert_eq!(foo.licenses, vec!["UNKNOWN"]);
+    }
+
+    #[test]
+    fn curate_by_purl_and_licenses() {
+        let curation = DeclarativeCuration::from_toml(CURATION).unwrap();
+
+        let mut bar = material("bar", "0.1.0", &["MIT", "UNKNOWN"]);
+        curation.curate_material(&mut bar);
+        assert_eq!(bar.annotations.get("usage"), Some(&"service".to_string()));
+        assert_eq!(
+            bar.license_expression.unwrap().to_string(),
+            "MIT AND BSD-3-Clause"
+        );
+    }
+
+    #[test]
+    fn chained_replacement() {
+        // not in alphabetical order, so that a sorted map would not chain them
+        let toml = "[[rules]]\nreplace_licenses = { LicenseRef-z = \"LicenseRef-y\", LicenseRef-y = \"MIT\" }\n";
+        let yaml = "rules:\n  - replace_licenses:\n      LicenseRef-z: LicenseRef-y\n      LicenseRef-y: MIT\n";
+
+        for curation in [
+            DeclarativeCuration::from_toml(toml).unwrap(),
+            DeclarativeCuration::from_yaml(yaml).unwrap(),
+        ] {
+            let mut foo = material("foo", "1.0.0", &["LicenseRef-z", "Apache-2.0"]);
+            curation.curate_material(&mut foo);
+            assert_eq!(foo.licenses, vec!["MIT", "Apache-2.0"]);
+        }
+    }
+
+    #[test]
+    fn purl_without_version() {
+        assert!(matches_purl("pkg:npm/%40angular/core", "pkg:npm/%40angular/core@15.0.0"));
+        assert!(matches_purl("pkg:npm/%40angular/core@15.0.0", "pkg:npm/%40angular/core@15.0.0"));
+        assert!(!matches_purl("pkg:npm/%40angular/core@14.0.0", "pkg:npm/%40angular/core@15.0.0"));
+        assert!(!matches_purl("pkg:cargo/core", "pkg:npm/%40angular/core@15.0.0"));
+    }
+
+    #[test]
+    fn exact_version() {
+        assert!(matches_version("1.0.0", "1.0.0"));
+        assert!(!matches_version("1.0.0", "1.0.1"));
+        assert!(matches_version("^1.0.0", "1.0.1"));
+        assert!(matches_version("2022-01", "2022-01"));
+    }
+
+    #[test]
+    fn yaml_curation() {
+        let curation = DeclarativeCuration::from_yaml(
+            "rules:\n  - match:\n      name: foo\n    licenses: [MIT]\n    comment: |\n      relicensed\n",
+        )
+        .unwrap();
+
+        let mut foo = material("foo", "1.0.0", &["UNKNOWN"]);
+        curation.curate_material(&mut foo);
+        assert_eq!(foo.licenses, vec!["MIT"]);
+        assert_eq!(foo.curation_reasons, vec!["relicensed"]);
+
+        assert!(DeclarativeCuration::from_yaml("rules:\n  - match: { unknown: foo }\n").is_err());
+    }
+}
@@ -0,0 +1,5 @@
+mod audit;
+mod declarative;
+
+pub use audit::CurationRecord;
+pub use declarative::DeclarativeCuration;
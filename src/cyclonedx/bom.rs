@@ -3,8 +3,12 @@ use crate::error::Error;
 
 use minidom::Element;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufRead, BufReader};
+
+/// Namespace of CycloneDX XML is followed by the spec version, e.g. `http://cyclonedx.org/schema/bom/1.5`.
+const CYCLONEDX_NS_PREFIX: &str = "http://cyclonedx.org/schema/bom/";
 
 #[derive(Deserialize, Debug, PartialEq)]
 pub enum BomFormat {
@@ -17,11 +21,28 @@ pub struct Bom {
     pub bom_format: BomFormat,
     pub spec_version: String,
     pub serial_number: Option<String>,
+    #[serde(default)]
     pub version: u32,
+    pub metadata: Option<Metadata>,
     pub components: Option<Components>,
+    pub dependencies: Option<Vec<Dependency>>,
+    // other field is umimplemented
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Metadata {
+    pub component: Option<Component>,
     // other field is umimplemented
 }
 
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Dependency {
+    #[serde(rename = "ref")]
+    pub dependency_ref: String,
+    pub depends_on: Option<Vec<String>>,
+}
+
 pub fn load_json_file(source: &File) -> Result<Bom, Error> {
     let reader = BufReader::new(source);
     let bom = serde_json::from_reader(reader)?;
@@ -30,15 +51,39 @@ pub fn load_json_file(source: &File) -> Result<Bom, Error> {
 }
 
 pub fn load_xml_file(source: &File) -> Result<Bom, Error> {
-    let reader = BufReader::new(source);
-    let element = Element::from_reader(reader)?;
+    load_xml(BufReader::new(source))
+}
+
+fn load_xml<R: BufRead>(reader: R) -> Result<Bom, Error> {
+    let element = match Element::from_reader(reader) {
+        Ok(element) => element,
+        Err(minidom::Error::MissingNamespace) => {
+            return Err(Error::Failure(format!(
+                "not a CycloneDX XML: root element must have namespace {}X.Y",
+                CYCLONEDX_NS_PREFIX
+            )))
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let spec_version = xml_spec_version(&element)?;
+
+    let mut unknown_elements: Vec<String> = Vec::new();
+    collect_unknown_license_elements(&element, &format!("/{}", element.name()), &mut unknown_elements);
+    if !unknown_elements.is_empty() {
+        return Err(Error::Failure(format!(
+            "unknown license elements in CycloneDX XML: {}",
+            unknown_elements.join(", ")
+        )));
+    }
 
     let mut bom = Bom {
         bom_format: BomFormat::CycloneDX,
-        spec_version: "".to_string(),
+        spec_version,
         serial_number: None,
         version: 0,
+        metadata: None,
         components: None,
+        dependencies: None,
     };
 
     if let Some(serial_number) = element.attr("serialNumber") {
@@ -50,19 +95,109 @@ pub fn load_xml_file(source: &File) -> Result<Bom, Error> {
     }
 
     for child in element.children() {
-        if child.name() == "components" {
-            bom.components = Some(Components::from(
-                child
-                    .children()
-                    .map(Component::from)
-                    .collect::<Vec<_>>(),
-            ));
+        match child.name() {
+            "metadata" => {
+                bom.metadata = Some(Metadata::from(child));
+            }
+            "components" => {
+                bom.components = Some(Components::from(
+                    child
+                        .children()
+                        .map(Component::from)
+                        .collect::<Vec<_>>(),
+                ));
+            }
+            "dependencies" => {
+                bom.dependencies = Some(child.children().map(Dependency::from).collect::<Vec<_>>());
+            }
+            _ => (),
         }
     }
 
     Ok(bom)
 }
 
+/// Spec version from the namespace of the root element. Documents other than CycloneDX are rejected.
+fn xml_spec_version(element: &Element) -> Result<String, Error> {
+    let ns = element.ns();
+    let version = ns
+        .strip_prefix(CYCLONEDX_NS_PREFIX)
+        .filter(|version| {
+            let mut numbers = version.split('.');
+            let is_number = |n: Option<&str>| n.is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+            is_number(numbers.next()) && is_number(numbers.next()) && numbers.next().is_none()
+        });
+
+    match version {
+        Some(version) if element.name() == "bom" => Ok(version.to_string()),
+        _ => Err(Error::Failure(format!(
+            "not a CycloneDX XML: root element must be bom of namespace {}X.Y, but {} of namespace \"{}\" is given",
+            CYCLONEDX_NS_PREFIX,
+            element.name(),
+            ns
+        ))),
+    }
+}
+
+/// Paths of elements in `licenses` other than `license` and `expression`, e.g.
+/// `/bom/components/component[2]/licenses/licence`. Elements of other namespaces are extensions, so they are allowed.
+fn collect_unknown_license_elements(element: &Element, path: &str, unknown_elements: &mut Vec<String>) {
+    let ns = element.ns();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for child in element.children() {
+        *counts.entry(child.name()).or_default() += 1;
+    }
+
+    let mut indexes: HashMap<&str, usize> = HashMap::new();
+    for child in element.children() {
+        let index = indexes.entry(child.name()).or_default();
+        *index += 1;
+        let child_path = match counts[child.name()] {
+            1 => format!("{}/{}", path, child.name()),
+            _ => format!("{}/{}[{}]", path, child.name(), index),
+        };
+
+        if element.name() == "licenses" && child.ns() == ns && !matches!(child.name(), "license" | "expression") {
+            unknown_elements.push(child_path);
+            continue;
+        }
+
+        collect_unknown_license_elements(child, &child_path, unknown_elements);
+    }
+}
+
+impl From<&Element> for Metadata {
+    fn from(element: &Element) -> Self {
+        let mut metadata = Metadata { component: None };
+
+        for child in element.children() {
+            if child.name() == "component" {
+                metadata.component = Some(Component::from(child));
+            }
+        }
+
+        metadata
+    }
+}
+
+impl From<&Element> for Dependency {
+    fn from(element: &Element) -> Self {
+        let depends_on = element
+            .children()
+            .filter(|child| child.name() == "dependency")
+            .filter_map(|child| child.attr("ref").map(|r| r.to_string()))
+            .collect::<Vec<_>>();
+
+        Dependency {
+            dependency_ref: element.attr("ref").unwrap_or_default().to_string(),
+            depends_on: match depends_on.is_empty() {
+                true => None,
+                false => Some(depends_on),
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
@@ -82,4 +217,82 @@ pub mod test {
 
         assert_eq!(bom.bom_format, BomFormat::CycloneDX);
     }
+
+    #[test]
+    fn load_xml_spec_version() {
+        let file = File::open("test/cyclonedx/basic.cdx.xml").unwrap();
+        assert_eq!(load_xml_file(&file).unwrap().spec_version, "1.3");
+
+        let file = File::open("test/cyclonedx/full.cdx.xml").unwrap();
+        assert_eq!(load_xml_file(&file).unwrap().spec_version, "1.6");
+
+        let file = File::open("test/spdx/basic.spdx.rdf").unwrap();
+        let err = load_xml_file(&file).unwrap_err().to_string();
+        assert!(err.contains("not a CycloneDX XML"), "{}", err);
+    }
+
+    #[test]
+    fn reject_xml_without_cyclonedx_namespace() {
+        for xml in [
+            r#"<bom version="1"><components/></bom>"#,
+            r#"<bom xmlns="http://example.com/bom/1.5"/>"#,
+            r#"<bom xmlns="http://cyclonedx.org/schema/bom/latest"/>"#,
+            r#"<sbom xmlns="http://cyclonedx.org/schema/bom/1.5"/>"#,
+        ] {
+            let err = load_xml(xml.as_bytes()).unwrap_err().to_string();
+            assert!(err.contains("not a CycloneDX XML"), "{}: {}", xml, err);
+        }
+    }
+
+    #[test]
+    fn report_unknown_license_elements() {
+        let element: Element = r#"<bom xmlns="http://cyclonedx.org/schema/bom/1.4" xmlns:ext="https://example.com/ext">
+  <components>
+    <component type="library">
+      <name>foo</name>
+      <licenses><license><id>MIT</id></license></licenses>
+    </component>
+    <component type="library">
+      <name>bar</name>
+      <licenses>
+        <licence><id>MIT</id></licence>
+        <ext:note>extension</ext:note>
+      </licenses>
+      <evidence>
+        <licenses><named>MIT</named></licenses>
+      </evidence>
+    </component>
+  </components>
+</bom>"#
+            .parse()
+            .unwrap();
+
+        let mut unknown_elements = Vec::new();
+        collect_unknown_license_elements(&element, "/bom", &mut unknown_elements);
+        let err = load_xml(String::from(&element).as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("/bom/components/component[2]/licenses/licence"), "{}", err);
+        assert_eq!(
+            unknown_elements,
+            vec![
+                "/bom/components/component[2]/licenses/licence",
+                "/bom/components/component[2]/evidence/licenses/named",
+            ]
+        );
+    }
+
+    #[test]
+    fn load_same_model_from_json_and_xml() {
+        let file = File::open("test/cyclonedx/full.cdx.json").unwrap();
+        let json_bom: Bom = load_json_file(&file).unwrap();
+        let file = File::open("test/cyclonedx/full.cdx.xml").unwrap();
+        let xml_bom: Bom = load_xml_file(&file).unwrap();
+
+        assert_eq!(json_bom.metadata, xml_bom.metadata);
+        assert_eq!(json_bom.components, xml_bom.components);
+
+        let components = json_bom.components.unwrap().0;
+        assert_eq!(components[0].scope, Some("required".to_string()));
+        assert_eq!(components[0].components.as_ref().unwrap().0[0].scope, Some("optional".to_string()));
+        assert!(components[1].evidence.as_ref().unwrap().licenses.is_some());
+    }
 }
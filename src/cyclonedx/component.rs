@@ -1,4 +1,4 @@
-use crate::cyclonedx::{LicenseChoice, Licenses};
+use crate::cyclonedx::Licenses;
 
 use minidom::Element;
 use serde::Deserialize;
@@ -7,15 +7,70 @@ use serde::Deserialize;
 #[serde(transparent)]
 pub struct Components(pub Vec<Component>);
 
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Component {
     #[serde(rename = "type")]
     pub component_type: String,
+    #[serde(rename = "bom-ref")]
+    pub bom_ref: Option<String>,
+    pub supplier: Option<OrganizationalEntity>,
+    /// Deprecated in CycloneDX 1.6 in favor of `authors`.
+    pub author: Option<String>,
+    pub authors: Option<Vec<OrganizationalContact>>,
+    pub publisher: Option<String>,
+    pub group: Option<String>,
     pub name: String,
     pub version: Option<String>,
+    pub scope: Option<String>,
+    pub hashes: Option<Vec<Hash>>,
     pub licenses: Option<Licenses>,
+    pub copyright: Option<String>,
+    pub cpe: Option<String>,
+    pub purl: Option<String>,
+    pub external_references: Option<Vec<ExternalReference>>,
+    pub properties: Option<Vec<Property>>,
     pub components: Option<Components>,
+    pub evidence: Option<Evidence>,
+    // other field is umimplemented
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct OrganizationalEntity {
+    pub name: Option<String>,
+    // other field is umimplemented
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct OrganizationalContact {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Hash {
+    pub alg: String,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ExternalReference {
+    #[serde(rename = "type")]
+    pub reference_type: String,
+    pub url: String,
+    pub comment: Option<String>,
+    // other field is umimplemented
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Property {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Evidence {
+    pub licenses: Option<Licenses>,
     // other field is umimplemented
 }
 
@@ -27,33 +82,62 @@ impl From<Vec<Component>> for Components {
 
 impl From<&Element> for Component {
     fn from(element: &Element) -> Self {
-        let mut component = Component {
-            component_type: "".to_string(),
-            name: "".to_string(),
-            version: None,
-            licenses: None,
-            components: None,
-        };
+        let mut component = Component::default();
 
         if let Some(component_type) = element.attr("type") {
             component.component_type = component_type.to_string();
         }
 
+        if let Some(bom_ref) = element.attr("bom-ref") {
+            component.bom_ref = Some(bom_ref.to_string());
+        }
+
         for child in element.children() {
             match child.name() {
+                "supplier" => {
+                    component.supplier = Some(OrganizationalEntity::from(child));
+                }
+                "author" => {
+                    component.author = Some(child.text());
+                }
+                "authors" => {
+                    component.authors = Some(child.children().map(OrganizationalContact::from).collect());
+                }
+                "publisher" => {
+                    component.publisher = Some(child.text());
+                }
+                "group" => {
+                    component.group = Some(child.text());
+                }
                 "name" => {
                     component.name = child.text();
                 }
                 "version" => {
                     component.version = Some(child.text());
                 }
+                "scope" => {
+                    component.scope = Some(child.text());
+                }
+                "hashes" => {
+                    component.hashes = Some(child.children().map(Hash::from).collect());
+                }
                 "licenses" => {
-                    component.licenses = Some(Licenses::from(
-                        child
-                            .children()
-                            .map(LicenseChoice::from)
-                            .collect::<Vec<_>>(),
-                    ));
+                    component.licenses = Some(Licenses::from(child));
+                }
+                "copyright" => {
+                    component.copyright = Some(child.text());
+                }
+                "cpe" => {
+                    component.cpe = Some(child.text());
+                }
+                "purl" => {
+                    component.purl = Some(child.text());
+                }
+                "externalReferences" => {
+                    component.external_references = Some(child.children().map(ExternalReference::from).collect());
+                }
+                "properties" => {
+                    component.properties = Some(child.children().map(Property::from).collect());
                 }
                 "components" => {
                     component.components = Some(Components::from(
@@ -63,6 +147,9 @@ impl From<&Element> for Component {
                             .collect::<Vec<_>>(),
                     ));
                 }
+                "evidence" => {
+                    component.evidence = Some(Evidence::from(child));
+                }
                 _ => (),
             }
         }
@@ -70,3 +157,93 @@ impl From<&Element> for Component {
         component
     }
 }
+
+impl From<&Element> for OrganizationalEntity {
+    fn from(element: &Element) -> Self {
+        let mut entity = OrganizationalEntity { name: None };
+
+        for child in element.children() {
+            if child.name() == "name" {
+                entity.name = Some(child.text());
+            }
+        }
+
+        entity
+    }
+}
+
+impl From<&Element> for OrganizationalContact {
+    fn from(element: &Element) -> Self {
+        let mut contact = OrganizationalContact { name: None, email: None };
+
+        for child in element.children() {
+            match child.name() {
+                "name" => {
+                    contact.name = Some(child.text());
+                }
+                "email" => {
+                    contact.email = Some(child.text());
+                }
+                _ => (),
+            }
+        }
+
+        contact
+    }
+}
+
+impl From<&Element> for Hash {
+    fn from(element: &Element) -> Self {
+        Hash {
+            alg: element.attr("alg").unwrap_or_default().to_string(),
+            content: element.text().trim().to_string(),
+        }
+    }
+}
+
+impl From<&Element> for ExternalReference {
+    fn from(element: &Element) -> Self {
+        let mut reference = ExternalReference {
+            reference_type: element.attr("type").unwrap_or_default().to_string(),
+            url: "".to_string(),
+            comment: None,
+        };
+
+        for child in element.children() {
+            match child.name() {
+                "url" => {
+                    reference.url = child.text().trim().to_string();
+                }
+                "comment" => {
+                    reference.comment = Some(child.text());
+                }
+                _ => (),
+            }
+        }
+
+        reference
+    }
+}
+
+impl From<&Element> for Property {
+    fn from(element: &Element) -> Self {
+        Property {
+            name: element.attr("name").unwrap_or_default().to_string(),
+            value: Some(element.text()),
+        }
+    }
+}
+
+impl From<&Element> for Evidence {
+    fn from(element: &Element) -> Self {
+        let mut evidence = Evidence { licenses: None };
+
+        for child in element.children() {
+            if child.name() == "licenses" {
+                evidence.licenses = Some(Licenses::from(child));
+            }
+        }
+
+        evidence
+    }
+}
@@ -6,18 +6,66 @@ use serde::Deserialize;
 pub struct Licenses(pub Vec<LicenseChoice>);
 
 #[derive(Debug, Deserialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
+#[serde(try_from = "LicenseChoiceJson")]
 pub enum LicenseChoice {
     License(License),
-    Expression(String),
+    Expression(Expression),
     // other field is umimplemented
 }
 
+impl LicenseChoice {
+    /// `declared` or `concluded` since CycloneDX 1.6.
+    pub fn acknowledgement(&self) -> Option<&str> {
+        match self {
+            LicenseChoice::License(license) => license.acknowledgement.as_deref(),
+            LicenseChoice::Expression(expression) => expression.acknowledgement.as_deref(),
+        }
+    }
+
+    /// License id, name or expression as written in BOM.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            LicenseChoice::License(license) => license.id.as_deref().or(license.name.as_deref()),
+            LicenseChoice::Expression(expression) => Some(&expression.expression),
+        }
+    }
+}
+
+/// CycloneDX 1.6 adds `acknowledgement` and `bom-ref` next to `expression`, so the choice is not a single key map.
+#[derive(Deserialize)]
+struct LicenseChoiceJson {
+    license: Option<License>,
+    expression: Option<String>,
+    acknowledgement: Option<String>,
+}
+
+impl TryFrom<LicenseChoiceJson> for LicenseChoice {
+    type Error = String;
+
+    fn try_from(json: LicenseChoiceJson) -> Result<Self, Self::Error> {
+        match (json.license, json.expression) {
+            (Some(license), None) => Ok(LicenseChoice::License(license)),
+            (None, Some(expression)) => Ok(LicenseChoice::Expression(Expression {
+                expression,
+                acknowledgement: json.acknowledgement,
+            })),
+            _ => Err("license choice must have either license or expression".to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct License {
     pub id: Option<String>,
     pub name: Option<String>,
+    pub acknowledgement: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Expression {
+    pub expression: String,
+    pub acknowledgement: Option<String>,
 }
 
 impl From<Vec<LicenseChoice>> for Licenses {
@@ -26,12 +74,27 @@ impl From<Vec<LicenseChoice>> for Licenses {
     }
 }
 
-impl From<&Element> for LicenseChoice {
+/// Elements other than `license` and `expression` are skipped. `load_xml_file` reports them before.
+impl From<&Element> for Licenses {
     fn from(element: &Element) -> Self {
+        Licenses::from(
+            element
+                .children()
+                .filter_map(LicenseChoice::from_element)
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+impl LicenseChoice {
+    fn from_element(element: &Element) -> Option<Self> {
         match element.name() {
-            "license" => LicenseChoice::License(License::from(element)),
-            "expression" => LicenseChoice::Expression(element.text()),
-            _ => LicenseChoice::Expression("".to_string()),
+            "license" => Some(LicenseChoice::License(License::from(element))),
+            "expression" => Some(LicenseChoice::Expression(Expression {
+                expression: element.text(),
+                acknowledgement: element.attr("acknowledgement").map(|a| a.to_string()),
+            })),
+            _ => None,
         }
     }
 }
@@ -41,6 +104,7 @@ impl From<&Element> for License {
         let mut license = License {
             id: None,
             name: None,
+            acknowledgement: element.attr("acknowledgement").map(|a| a.to_string()),
         };
 
         for child in element.children() {
@@ -1,7 +1,9 @@
 mod bom;
 mod component;
 mod license;
+mod protobuf;
 
 pub use bom::{load_json_file, load_xml_file, Bom};
 pub use component::{Component, Components};
-pub use license::{License, LicenseChoice, Licenses};
+pub use license::{LicenseChoice, Licenses};
+pub use protobuf::load_protobuf_file;
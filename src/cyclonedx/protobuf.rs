@@ -0,0 +1,689 @@
+use crate::cyclonedx::bom::{BomFormat, Dependency, Metadata};
+use crate::cyclonedx::component::{
+    Evidence, ExternalReference, Hash, OrganizationalContact, OrganizationalEntity, Property,
+};
+use crate::cyclonedx::license::{Expression, License};
+use crate::cyclonedx::{Bom, Component, Components, LicenseChoice, Licenses};
+use crate::error::Error;
+
+use prost::Message;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+/// Load CycloneDX protobuf (`.cdx.bin`) into the same model as JSON and XML.
+pub fn load_protobuf_file(source: &File) -> Result<Bom, Error> {
+    let mut bytes = Vec::new();
+    BufReader::new(source).read_to_end(&mut bytes)?;
+
+    let bom = proto::Bom::decode(bytes.as_slice())?;
+
+    Ok(Bom::from(bom))
+}
+
+/// Messages of the official `bom-1.6.proto` (package `cyclonedx.v1_6`). Only the fields hatto uses are declared,
+/// and the other fields are skipped by decoder. Tags are the same through CycloneDX 1.3 to 1.6.
+pub(crate) mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Bom {
+        #[prost(string, tag = "1")]
+        pub spec_version: String,
+        #[prost(int32, optional, tag = "2")]
+        pub version: Option<i32>,
+        #[prost(string, optional, tag = "3")]
+        pub serial_number: Option<String>,
+        #[prost(message, optional, tag = "4")]
+        pub metadata: Option<Metadata>,
+        #[prost(message, repeated, tag = "5")]
+        pub components: Vec<Component>,
+        #[prost(message, repeated, tag = "8")]
+        pub dependencies: Vec<Dependency>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Metadata {
+        #[prost(message, optional, tag = "4")]
+        pub component: Option<Component>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Dependency {
+        #[prost(string, tag = "1")]
+        pub r#ref: String,
+        #[prost(message, repeated, tag = "2")]
+        pub dependencies: Vec<Dependency>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Component {
+        #[prost(enumeration = "Classification", tag = "1")]
+        pub r#type: i32,
+        #[prost(string, optional, tag = "3")]
+        pub bom_ref: Option<String>,
+        #[prost(message, optional, tag = "4")]
+        pub supplier: Option<OrganizationalEntity>,
+        #[prost(string, optional, tag = "5")]
+        pub author: Option<String>,
+        #[prost(string, optional, tag = "6")]
+        pub publisher: Option<String>,
+        #[prost(string, optional, tag = "7")]
+        pub group: Option<String>,
+        #[prost(string, tag = "8")]
+        pub name: String,
+        #[prost(string, optional, tag = "9")]
+        pub version: Option<String>,
+        #[prost(enumeration = "Scope", optional, tag = "11")]
+        pub scope: Option<i32>,
+        #[prost(message, repeated, tag = "12")]
+        pub hashes: Vec<Hash>,
+        #[prost(message, repeated, tag = "13")]
+        pub licenses: Vec<LicenseChoice>,
+        #[prost(string, optional, tag = "14")]
+        pub copyright: Option<String>,
+        #[prost(string, optional, tag = "15")]
+        pub cpe: Option<String>,
+        #[prost(string, optional, tag = "16")]
+        pub purl: Option<String>,
+        #[prost(message, repeated, tag = "20")]
+        pub external_references: Vec<ExternalReference>,
+        #[prost(message, repeated, tag = "21")]
+        pub components: Vec<Component>,
+        #[prost(message, repeated, tag = "22")]
+        pub properties: Vec<Property>,
+        #[prost(message, optional, tag = "23")]
+        pub evidence: Option<Evidence>,
+        #[prost(message, repeated, tag = "29")]
+        pub authors: Vec<OrganizationalContact>,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Classification {
+        Null = 0,
+        Application = 1,
+        Framework = 2,
+        Library = 3,
+        OperatingSystem = 4,
+        Device = 5,
+        File = 6,
+        Container = 7,
+        Firmware = 8,
+        DeviceDriver = 9,
+        Platform = 10,
+        MachineLearningModel = 11,
+        Data = 12,
+        CryptographicAsset = 13,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Scope {
+        Unspecified = 0,
+        Required = 1,
+        Optional = 2,
+        Excluded = 3,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct OrganizationalEntity {
+        #[prost(string, optional, tag = "1")]
+        pub name: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct OrganizationalContact {
+        #[prost(string, optional, tag = "1")]
+        pub name: Option<String>,
+        #[prost(string, optional, tag = "2")]
+        pub email: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Hash {
+        #[prost(enumeration = "HashAlg", tag = "1")]
+        pub alg: i32,
+        #[prost(string, tag = "2")]
+        pub value: String,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum HashAlg {
+        Null = 0,
+        Md5 = 1,
+        Sha1 = 2,
+        Sha256 = 3,
+        Sha384 = 4,
+        Sha512 = 5,
+        Sha3_256 = 6,
+        Sha3_384 = 7,
+        Sha3_512 = 8,
+        Blake2b256 = 9,
+        Blake2b384 = 10,
+        Blake2b512 = 11,
+        Blake3 = 12,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct LicenseChoice {
+        #[prost(oneof = "license_choice::Choice", tags = "1, 2")]
+        pub choice: Option<license_choice::Choice>,
+        #[prost(enumeration = "LicenseAcknowledgementEnumeration", optional, tag = "3")]
+        pub acknowledgement: Option<i32>,
+    }
+
+    pub mod license_choice {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Choice {
+            #[prost(message, tag = "1")]
+            License(super::License),
+            #[prost(string, tag = "2")]
+            Expression(String),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct License {
+        #[prost(oneof = "license::License", tags = "1, 2")]
+        pub license: Option<license::License>,
+        #[prost(enumeration = "LicenseAcknowledgementEnumeration", optional, tag = "8")]
+        pub acknowledgement: Option<i32>,
+    }
+
+    pub mod license {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum License {
+            #[prost(string, tag = "1")]
+            Id(String),
+            #[prost(string, tag = "2")]
+            Name(String),
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum LicenseAcknowledgementEnumeration {
+        Unspecified = 0,
+        Declared = 1,
+        Concluded = 2,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExternalReference {
+        #[prost(enumeration = "ExternalReferenceType", tag = "1")]
+        pub r#type: i32,
+        #[prost(string, tag = "2")]
+        pub url: String,
+        #[prost(string, optional, tag = "3")]
+        pub comment: Option<String>,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum ExternalReferenceType {
+        Other = 0,
+        Vcs = 1,
+        IssueTracker = 2,
+        Website = 3,
+        Advisories = 4,
+        Bom = 5,
+        MailingList = 6,
+        Social = 7,
+        Chat = 8,
+        Documentation = 9,
+        Support = 10,
+        Distribution = 11,
+        License = 12,
+        BuildMeta = 13,
+        BuildSystem = 14,
+        SecurityContact = 15,
+        Attestation = 16,
+        ThreatModel = 17,
+        AdversaryModel = 18,
+        RiskAssessment = 19,
+        DistributionIntake = 20,
+        VulnerabilityAssertion = 21,
+        ExploitabilityStatement = 22,
+        PentestReport = 23,
+        StaticAnalysisReport = 24,
+        DynamicAnalysisReport = 25,
+        RuntimeAnalysisReport = 26,
+        ComponentAnalysisReport = 27,
+        MaturityReport = 28,
+        CertificationReport = 29,
+        QualityMetrics = 30,
+        CodifiedInfrastructure = 31,
+        ModelCard = 32,
+        Poam = 33,
+        Log = 34,
+        Configuration = 35,
+        Evidence = 36,
+        Formulation = 37,
+        SourceDistribution = 38,
+        ElectronicSignature = 39,
+        DigitalSignature = 40,
+        Rfc9116 = 41,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Property {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, optional, tag = "2")]
+        pub value: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Evidence {
+        #[prost(message, repeated, tag = "1")]
+        pub licenses: Vec<LicenseChoice>,
+    }
+}
+
+/// Repeated fields are empty rather than absent, but the model of JSON has `None` for absent fields.
+fn non_empty<T>(values: Vec<T>) -> Option<Vec<T>> {
+    match values.is_empty() {
+        true => None,
+        false => Some(values),
+    }
+}
+
+fn licenses(licenses: Vec<proto::LicenseChoice>) -> Option<Licenses> {
+    non_empty(licenses.into_iter().filter_map(license_choice).collect()).map(Licenses::from)
+}
+
+fn license_choice(choice: proto::LicenseChoice) -> Option<LicenseChoice> {
+    let acknowledgement = choice.acknowledgement.and_then(acknowledgement);
+
+    match choice.choice? {
+        proto::license_choice::Choice::License(license) => {
+            let (id, name) = match license.license {
+                Some(proto::license::License::Id(id)) => (Some(id), None),
+                Some(proto::license::License::Name(name)) => (None, Some(name)),
+                None => (None, None),
+            };
+            Some(LicenseChoice::License(License {
+                id,
+                name,
+                acknowledgement: license.acknowledgement.and_then(self::acknowledgement),
+            }))
+        }
+        proto::license_choice::Choice::Expression(expression) => Some(LicenseChoice::Expression(Expression {
+            expression,
+            acknowledgement,
+        })),
+    }
+}
+
+fn acknowledgement(value: i32) -> Option<String> {
+    match proto::LicenseAcknowledgementEnumeration::try_from(value).ok()? {
+        proto::LicenseAcknowledgementEnumeration::Declared => Some("declared".to_string()),
+        proto::LicenseAcknowledgementEnumeration::Concluded => Some("concluded".to_string()),
+        proto::LicenseAcknowledgementEnumeration::Unspecified => None,
+    }
+}
+
+/// Component type as written in JSON, e.g. `operating-system`.
+fn classification(value: i32) -> String {
+    use proto::Classification::*;
+
+    let classification = match proto::Classification::try_from(value) {
+        Ok(classification) => classification,
+        Err(_) => return "".to_string(),
+    };
+    match classification {
+        Null => "",
+        Application => "application",
+        Framework => "framework",
+        Library => "library",
+        OperatingSystem => "operating-system",
+        Device => "device",
+        File => "file",
+        Container => "container",
+        Firmware => "firmware",
+        DeviceDriver => "device-driver",
+        Platform => "platform",
+        MachineLearningModel => "machine-learning-model",
+        Data => "data",
+        CryptographicAsset => "cryptographic-asset",
+    }
+    .to_string()
+}
+
+fn scope(value: i32) -> Option<String> {
+    match proto::Scope::try_from(value).ok()? {
+        proto::Scope::Required => Some("required".to_string()),
+        proto::Scope::Optional => Some("optional".to_string()),
+        proto::Scope::Excluded => Some("excluded".to_string()),
+        proto::Scope::Unspecified => None,
+    }
+}
+
+/// Hash algorithm as written in JSON, e.g. `SHA-256`.
+fn hash_alg(value: i32) -> String {
+    use proto::HashAlg::*;
+
+    let alg = match proto::HashAlg::try_from(value) {
+        Ok(alg) => alg,
+        Err(_) => return "".to_string(),
+    };
+    match alg {
+        Null => "",
+        Md5 => "MD5",
+        Sha1 => "SHA-1",
+        Sha256 => "SHA-256",
+        Sha384 => "SHA-384",
+        Sha512 => "SHA-512",
+        Sha3_256 => "SHA3-256",
+        Sha3_384 => "SHA3-384",
+        Sha3_512 => "SHA3-512",
+        Blake2b256 => "BLAKE2b-256",
+        Blake2b384 => "BLAKE2b-384",
+        Blake2b512 => "BLAKE2b-512",
+        Blake3 => "BLAKE3",
+    }
+    .to_string()
+}
+
+/// External reference type as written in JSON, e.g. `issue-tracker`.
+fn external_reference_type(value: i32) -> String {
+    use proto::ExternalReferenceType::*;
+
+    let reference_type = proto::ExternalReferenceType::try_from(value).unwrap_or(Other);
+    match reference_type {
+        Other => "other",
+        Vcs => "vcs",
+        IssueTracker => "issue-tracker",
+        Website => "website",
+        Advisories => "advisories",
+        Bom => "bom",
+        MailingList => "mailing-list",
+        Social => "social",
+        Chat => "chat",
+        Documentation => "documentation",
+        Support => "support",
+        Distribution => "distribution",
+        License => "license",
+        BuildMeta => "build-meta",
+        BuildSystem => "build-system",
+        SecurityContact => "security-contact",
+        Attestation => "attestation",
+        ThreatModel => "threat-model",
+        AdversaryModel => "adversary-model",
+        RiskAssessment => "risk-assessment",
+        DistributionIntake => "distribution-intake",
+        VulnerabilityAssertion => "vulnerability-assertion",
+        ExploitabilityStatement => "exploitability-statement",
+        PentestReport => "pentest-report",
+        StaticAnalysisReport => "static-analysis-report",
+        DynamicAnalysisReport => "dynamic-analysis-report",
+        RuntimeAnalysisReport => "runtime-analysis-report",
+        ComponentAnalysisReport => "component-analysis-report",
+        MaturityReport => "maturity-report",
+        CertificationReport => "certification-report",
+        QualityMetrics => "quality-metrics",
+        CodifiedInfrastructure => "codified-infrastructure",
+        ModelCard => "model-card",
+        Poam => "poam",
+        Log => "log",
+        Configuration => "configuration",
+        Evidence => "evidence",
+        Formulation => "formulation",
+        SourceDistribution => "source-distribution",
+        ElectronicSignature => "electronic-signature",
+        DigitalSignature => "digital-signature",
+        Rfc9116 => "rfc-9116",
+    }
+    .to_string()
+}
+
+impl From<proto::Bom> for Bom {
+    fn from(bom: proto::Bom) -> Self {
+        Bom {
+            bom_format: BomFormat::CycloneDX,
+            spec_version: bom.spec_version,
+            serial_number: bom.serial_number,
+            version: bom.version.and_then(|version| u32::try_from(version).ok()).unwrap_or_default(),
+            metadata: bom.metadata.map(|metadata| Metadata {
+                component: metadata.component.map(Component::from),
+            }),
+            components: non_empty(bom.components.into_iter().map(Component::from).collect()).map(Components::from),
+            dependencies: non_empty(
+                bom.dependencies
+                    .into_iter()
+                    .map(|dependency| Dependency {
+                        dependency_ref: dependency.r#ref,
+                        depends_on: non_empty(dependency.dependencies.into_iter().map(|d| d.r#ref).collect()),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<proto::Component> for Component {
+    fn from(component: proto::Component) -> Self {
+        Component {
+            component_type: classification(component.r#type),
+            bom_ref: component.bom_ref,
+            supplier: component
+                .supplier
+                .map(|supplier| OrganizationalEntity { name: supplier.name }),
+            author: component.author,
+            authors: non_empty(
+                component
+                    .authors
+                    .into_iter()
+                    .map(|author| OrganizationalContact {
+                        name: author.name,
+                        email: author.email,
+                    })
+                    .collect(),
+            ),
+            publisher: component.publisher,
+            group: component.group,
+            name: component.name,
+            version: component.version,
+            scope: component.scope.and_then(scope),
+            hashes: non_empty(
+                component
+                    .hashes
+                    .into_iter()
+                    .map(|hash| Hash {
+                        alg: hash_alg(hash.alg),
+                        content: hash.value,
+                    })
+                    .collect(),
+            ),
+            licenses: licenses(component.licenses),
+            copyright: component.copyright,
+            cpe: component.cpe,
+            purl: component.purl,
+            external_references: non_empty(
+                component
+                    .external_references
+                    .into_iter()
+                    .map(|reference| ExternalReference {
+                        reference_type: external_reference_type(reference.r#type),
+                        url: reference.url,
+                        comment: reference.comment,
+                    })
+                    .collect(),
+            ),
+            properties: non_empty(
+                component
+                    .properties
+                    .into_iter()
+                    .map(|property| Property {
+                        name: property.name,
+                        value: property.value,
+                    })
+                    .collect(),
+            ),
+            components: non_empty(component.components.into_iter().map(Component::from).collect())
+                .map(Components::from),
+            evidence: component.evidence.map(|evidence| Evidence {
+                licenses: licenses(evidence.licenses),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::cyclonedx::load_json_file;
+
+    fn some(value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    fn license_id(id: &str, acknowledgement: Option<proto::LicenseAcknowledgementEnumeration>) -> proto::LicenseChoice {
+        proto::LicenseChoice {
+            choice: Some(proto::license_choice::Choice::License(proto::License {
+                license: Some(proto::license::License::Id(id.to_string())),
+                acknowledgement: acknowledgement.map(|a| a as i32),
+            })),
+            acknowledgement: None,
+        }
+    }
+
+    /// The same BOM as `test/cyclonedx/full.cdx.json`, encoded to `test/cyclonedx/full.cdx.bin`.
+    fn full_bom() -> proto::Bom {
+        let foo_core = proto::Component {
+            r#type: proto::Classification::Library as i32,
+            bom_ref: some("pkg:npm/%40heriet/foo-core@1.0.0"),
+            author: some("heriet"),
+            name: "foo-core".to_string(),
+            version: some("1.0.0"),
+            scope: Some(proto::Scope::Optional as i32),
+            licenses: vec![license_id("MIT", Some(proto::LicenseAcknowledgementEnumeration::Concluded))],
+            ..Default::default()
+        };
+
+        let foo = proto::Component {
+            r#type: proto::Classification::Library as i32,
+            bom_ref: some("pkg:npm/%40heriet/foo@1.0.0"),
+            supplier: Some(proto::OrganizationalEntity { name: some("heriet") }),
+            authors: vec![
+                proto::OrganizationalContact {
+                    name: some("heriet"),
+                    email: some("heriet@heriet.info"),
+                },
+                proto::OrganizationalContact {
+                    name: some("contributors"),
+                    email: None,
+                },
+            ],
+            publisher: some("heriet publisher"),
+            group: some("@heriet"),
+            name: "foo".to_string(),
+            version: some("1.0.0"),
+            scope: Some(proto::Scope::Required as i32),
+            hashes: vec![proto::Hash {
+                alg: proto::HashAlg::Sha256 as i32,
+                value: "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".to_string(),
+            }],
+            licenses: vec![proto::LicenseChoice {
+                choice: Some(proto::license_choice::Choice::Expression("MIT OR Apache-2.0".to_string())),
+                acknowledgement: Some(proto::LicenseAcknowledgementEnumeration::Declared as i32),
+            }],
+            copyright: some("Copyright (c) 2024 heriet"),
+            cpe: some("cpe:2.3:a:heriet:foo:1.0.0:*:*:*:*:*:*:*"),
+            purl: some("pkg:npm/%40heriet/foo@1.0.0"),
+            external_references: vec![
+                proto::ExternalReference {
+                    r#type: proto::ExternalReferenceType::Vcs as i32,
+                    url: "https://github.com/heriet/foo".to_string(),
+                    comment: some("source"),
+                },
+                proto::ExternalReference {
+                    r#type: proto::ExternalReferenceType::Website as i32,
+                    url: "https://heriet.info/foo".to_string(),
+                    comment: None,
+                },
+            ],
+            properties: vec![
+                proto::Property {
+                    name: "usage".to_string(),
+                    value: some("service"),
+                },
+                proto::Property {
+                    name: "cdx:npm:package:development".to_string(),
+                    value: some("false"),
+                },
+            ],
+            components: vec![foo_core],
+            ..Default::default()
+        };
+
+        let bar = proto::Component {
+            r#type: proto::Classification::Library as i32,
+            bom_ref: some("pkg:npm/bar@2.0.0"),
+            name: "bar".to_string(),
+            version: some("2.0.0"),
+            scope: Some(proto::Scope::Excluded as i32),
+            evidence: Some(proto::Evidence {
+                licenses: vec![license_id("BSD-3-Clause", None)],
+            }),
+            ..Default::default()
+        };
+
+        let dependency = |r: &str, refs: &[&str]| proto::Dependency {
+            r#ref: r.to_string(),
+            dependencies: refs
+                .iter()
+                .map(|r| proto::Dependency {
+                    r#ref: r.to_string(),
+                    dependencies: vec![],
+                })
+                .collect(),
+        };
+
+        proto::Bom {
+            spec_version: "1.6".to_string(),
+            version: Some(1),
+            serial_number: some("urn:uuid:3e671687-395b-41f5-a30f-a58921a69b79"),
+            metadata: Some(proto::Metadata {
+                component: Some(proto::Component {
+                    r#type: proto::Classification::Application as i32,
+                    bom_ref: some("app"),
+                    name: "app".to_string(),
+                    version: some("0.1.0"),
+                    ..Default::default()
+                }),
+            }),
+            components: vec![foo, bar],
+            dependencies: vec![
+                dependency("app", &["pkg:npm/%40heriet/foo@1.0.0"]),
+                dependency(
+                    "pkg:npm/%40heriet/foo@1.0.0",
+                    &["pkg:npm/%40heriet/foo-core@1.0.0", "pkg:npm/bar@2.0.0"],
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn load_same_model_from_json_and_protobuf() {
+        let json_bom = load_json_file(&File::open("test/cyclonedx/full.cdx.json").unwrap()).unwrap();
+        let protobuf_bom = load_protobuf_file(&File::open("test/cyclonedx/full.cdx.bin").unwrap()).unwrap();
+
+        assert_eq!(protobuf_bom, json_bom);
+    }
+
+    #[test]
+    fn protobuf_round_trip() {
+        let bytes = std::fs::read("test/cyclonedx/full.cdx.bin").unwrap();
+        let decoded = proto::Bom::decode(bytes.as_slice()).unwrap();
+
+        assert_eq!(decoded, full_bom());
+        assert_eq!(decoded.encode_to_vec(), bytes);
+    }
+
+    #[test]
+    fn reject_invalid_protobuf() {
+        let file = File::open("test/cyclonedx/full.cdx.json").unwrap();
+
+        assert!(matches!(load_protobuf_file(&file), Err(Error::Protobuf(_))));
+    }
+}
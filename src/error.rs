@@ -15,15 +15,33 @@ pub enum Error {
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
 
+    #[error(transparent)]
+    SerdeYaml(#[from] serde_yaml::Error),
+
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+
     #[error(transparent)]
     Spdx(#[from] spdx_rs::error::SpdxError),
 
     #[error(transparent)]
     SpdxExpression(#[from] spdx_rs::models::SpdxExpressionError),
 
+    #[error("License expression error: {0}")]
+    LicenseExpression(String),
+
+    #[error("Invalid package URL: {0}")]
+    Purl(String),
+
     #[error(transparent)]
     Minidom(#[from] minidom::Error),
 
+    #[error(transparent)]
+    Protobuf(#[from] prost::DecodeError),
+
+    #[error(transparent)]
+    Xlsx(#[from] calamine::XlsxError),
+
     #[error(transparent)]
     ParseInt(#[from] std::num::ParseIntError),
 }
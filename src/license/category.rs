@@ -0,0 +1,169 @@
+use crate::error::Error;
+use crate::license::LicenseExpression;
+
+use csv::ReaderBuilder;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+
+const BUILTIN_CATEGORIES_TSV: &str = include_str!("../../data/license-categories.tsv");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LicenseCategory {
+    Permissive,
+    WeakCopyleft,
+    StrongCopyleft,
+    NetworkCopyleft,
+    Proprietary,
+    PublicDomain,
+    Unknown,
+}
+
+impl fmt::Display for LicenseCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            LicenseCategory::Permissive => "permissive",
+            LicenseCategory::WeakCopyleft => "weak-copyleft",
+            LicenseCategory::StrongCopyleft => "strong-copyleft",
+            LicenseCategory::NetworkCopyleft => "network-copyleft",
+            LicenseCategory::Proprietary => "proprietary",
+            LicenseCategory::PublicDomain => "public-domain",
+            LicenseCategory::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CategoryTsv {
+    license: String,
+    category: LicenseCategory,
+}
+
+/// Classifies licenses into categories by a table of license identifiers.
+///
+/// A license ending with `*` in the table matches any suffix, and the longest match wins.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct Classifier {
+    exact: HashMap<String, LicenseCategory>,
+    prefixes: Vec<(String, LicenseCategory)>,
+}
+
+impl Classifier {
+    pub fn new() -> Result<Self, Error> {
+        let mut classifier = Classifier {
+            exact: HashMap::new(),
+            prefixes: Vec::new(),
+        };
+        classifier.load(BUILTIN_CATEGORIES_TSV.as_bytes())?;
+
+        Ok(classifier)
+    }
+
+    /// Load category table. Entries override the entries loaded before.
+    pub fn load<R: Read>(&mut self, source: R) -> Result<(), Error> {
+        let mut tsv_reader = ReaderBuilder::new()
+            .has_headers(true)
+            .delimiter(b'\t')
+            .comment(Some(b'#'))
+            .from_reader(source);
+        for row in tsv_reader.deserialize() {
+            let tsv: CategoryTsv = row?;
+            let license = tsv.license.trim().to_lowercase();
+
+            match license.strip_suffix('*') {
+                Some(prefix) => {
+                    self.prefixes.retain(|(p, _)| p != prefix);
+                    self.prefixes.push((prefix.to_string(), tsv.category));
+                }
+                None => {
+                    self.exact.insert(license, tsv.category);
+                }
+            }
+        }
+
+        // longest prefix first
+        self.prefixes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        Ok(())
+    }
+
+    /// Category of a license identifier. `X+` and `X WITH E` are classified as `X`.
+    pub fn category(&self, license: &str) -> LicenseCategory {
+        let license = license.trim();
+        let license = match LicenseExpression::parse(license) {
+            Ok(expression) => match expression.simple_licenses().as_slice() {
+                [simple] => simple.identifier.clone(),
+                _ => license.to_string(),
+            },
+            Err(_) => license.to_string(),
+        };
+        let key = license.to_lowercase();
+
+        if let Some(category) = self.exact.get(&key) {
+            return *category;
+        }
+
+        self.prefixes
+            .iter()
+            .find(|(prefix, _)| key.starts_with(prefix))
+            .map(|(_, category)| *category)
+            .unwrap_or(LicenseCategory::Unknown)
+    }
+
+    pub fn categories(&self, licenses: &[String]) -> HashMap<String, String> {
+        licenses
+            .iter()
+            .map(|license| (license.clone(), self.category(license).to_string()))
+            .collect()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Classifier {
+    fn category_of(&self, license_id: &str) -> String {
+        self.category(license_id).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_category() {
+        let classifier = Classifier::new().unwrap();
+
+        assert_eq!(classifier.category("MIT"), LicenseCategory::Permissive);
+        assert_eq!(classifier.category("BSD-3-Clause"), LicenseCategory::Permissive);
+        assert_eq!(classifier.category("LGPL-2.1-only"), LicenseCategory::WeakCopyleft);
+        assert_eq!(classifier.category("GPL-2.0+"), LicenseCategory::StrongCopyleft);
+        assert_eq!(
+            classifier.category("GPL-2.0-only WITH Classpath-exception-2.0"),
+            LicenseCategory::StrongCopyleft
+        );
+        assert_eq!(classifier.category("AGPL-3.0-only"), LicenseCategory::NetworkCopyleft);
+        assert_eq!(classifier.category("CC0-1.0"), LicenseCategory::PublicDomain);
+        assert_eq!(classifier.category("BUSL-1.1"), LicenseCategory::Proprietary);
+        assert_eq!(classifier.category("UNKNOWN"), LicenseCategory::Unknown);
+    }
+
+    #[test]
+    fn override_category() {
+        let mut classifier = Classifier::new().unwrap();
+        classifier
+            .load("license\tcategory\nLGPL-*\tstrong-copyleft\nLicenseRef-foo\tpermissive\n".as_bytes())
+            .unwrap();
+
+        assert_eq!(classifier.category("LGPL-3.0-only"), LicenseCategory::StrongCopyleft);
+        assert_eq!(classifier.category("LicenseRef-foo"), LicenseCategory::Permissive);
+
+        assert!(classifier.load("license\tcategory\nfoo\tbar\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use crate::error::Error;
+use crate::license::category::Classifier;
+use crate::license::LicenseExpression;
+use crate::model::material::Material;
+
+use csv::ReaderBuilder;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Read;
+
+const BUILTIN_COMPATIBILITY_TSV: &str = include_str!("../../data/license-compatibility.tsv");
+
+const ANY_LICENSE: &str = "*";
+
+/// Specificity of keys. Identifiers are ranked from 0 by their order.
+const CATEGORY_RANK: usize = 100;
+const ANY_LICENSE_RANK: usize = 200;
+
+#[derive(Debug, Deserialize)]
+struct CompatibilityTsv {
+    inbound: String,
+    outbound: String,
+    compatible: String,
+}
+
+/// Compatibility matrix of inbound licenses (licenses of materials) and outbound licenses (the project license).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct Compatibility {
+    classifier: Classifier,
+    entries: HashMap<(String, String), bool>,
+}
+
+impl Compatibility {
+    pub fn new(classifier: Classifier) -> Result<Self, Error> {
+        let mut compatibility = Compatibility {
+            classifier,
+            entries: HashMap::new(),
+        };
+        compatibility.load(BUILTIN_COMPATIBILITY_TSV.as_bytes())?;
+
+        Ok(compatibility)
+    }
+
+    /// Load compatibility table. Entries override the entries loaded before.
+    pub fn load<R: Read>(&mut self, source: R) -> Result<(), Error> {
+        let mut tsv_reader = ReaderBuilder::new()
+            .has_headers(true)
+            .delimiter(b'\t')
+            .comment(Some(b'#'))
+            .from_reader(source);
+        for row in tsv_reader.deserialize() {
+            let tsv: CompatibilityTsv = row?;
+            let compatible = match tsv.compatible.trim().to_lowercase().as_str() {
+                "yes" | "true" => true,
+                "no" | "false" => false,
+                other => {
+                    return Err(Error::Failure(format!(
+                        "compatible must be yes or no, but {} is given",
+                        other
+                    )))
+                }
+            };
+            self.entries
+                .insert((key(&tsv.inbound), key(&tsv.outbound)), compatible);
+        }
+
+        Ok(())
+    }
+
+    /// Whether the inbound expression can be distributed under the outbound expression.
+    ///
+    /// One of `OR` alternatives of inbound must be compatible with every license of outbound, because the project
+    /// must satisfy all the licenses it offers.
+    pub fn is_compatible(&self, inbound: &LicenseExpression, outbound: &LicenseExpression) -> bool {
+        let outbound_licenses = outbound.alternative_ids().concat();
+
+        inbound.alternative_ids().iter().any(|alternative| {
+            alternative.iter().all(|inbound_license| {
+                outbound_licenses
+                    .iter()
+                    .all(|outbound_license| self.is_license_compatible(inbound_license, outbound_license))
+            })
+        })
+    }
+
+    /// Licenses are single license of expression, e.g. `MIT`, `GPL-2.0+` or `GPL-2.0-only WITH Classpath-exception-2.0`.
+    fn is_license_compatible(&self, inbound: &str, outbound: &str) -> bool {
+        if inbound == outbound {
+            return true;
+        }
+
+        let inbound_keys = self.lookup_keys(inbound);
+        let outbound_keys = self.lookup_keys(outbound);
+
+        // both sides go from identifier to category and then `*`, so that `[permissive] GPL-3.0-only` is used
+        // before `MIT *` rather than the inbound side deciding alone
+        let mut pairs = inbound_keys
+            .iter()
+            .flat_map(|(inbound_key, inbound_rank)| {
+                outbound_keys.iter().map(move |(outbound_key, outbound_rank)| {
+                    (
+                        (*inbound_rank.max(outbound_rank), inbound_rank + outbound_rank),
+                        inbound_key,
+                        outbound_key,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        pairs.sort_by_key(|(rank, _, _)| *rank);
+
+        pairs
+            .into_iter()
+            .find_map(|(_, inbound_key, outbound_key)| {
+                self.entries.get(&(inbound_key.clone(), outbound_key.clone())).copied()
+            })
+            .unwrap_or(false)
+    }
+
+    /// Keys with their specificity, from the most specific: `X WITH E`, `X+` as `X-or-later`, `X`, `[category]` and
+    /// `*`.
+    fn lookup_keys(&self, license: &str) -> Vec<(String, usize)> {
+        let mut identifiers = vec![key(license)];
+        if let Ok(expression) = LicenseExpression::parse(license) {
+            if let [simple] = expression.simple_licenses().as_slice() {
+                if simple.or_later {
+                    identifiers.push(key(&format!("{}-or-later", simple.identifier)));
+                }
+                identifiers.push(key(&simple.identifier));
+            }
+        }
+        identifiers.dedup();
+
+        let mut keys = identifiers
+            .into_iter()
+            .enumerate()
+            .map(|(rank, identifier)| (identifier, rank))
+            .collect::<Vec<_>>();
+        keys.push((format!("[{}]", self.classifier.category(license)), CATEGORY_RANK));
+        keys.push((ANY_LICENSE.to_string(), ANY_LICENSE_RANK));
+        keys
+    }
+
+    /// Error message if licenses of material are not compatible with the project license.
+    pub fn check_material(&self, material: &Material, project_license: &LicenseExpression) -> Option<String> {
+        if material.licenses.is_empty() {
+            return None;
+        }
+
+        let compatible = match &material.license_expression {
+            Some(expression) => self.is_compatible(expression, project_license),
+            None => false,
+        };
+
+        match compatible {
+            true => None,
+            false => Some(format!(
+                "{} is not compatible with project license {}",
+                match &material.license_expression {
+                    Some(expression) => expression.to_string(),
+                    None => material.licenses.join(","),
+                },
+                project_license
+            )),
+        }
+    }
+}
+
+fn key(license: &str) -> String {
+    license.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Compatibility {
+    #[pyo3(name = "is_compatible")]
+    fn py_is_compatible(&self, inbound: &PyAny, outbound: &PyAny) -> PyResult<bool> {
+        let inbound = LicenseExpression::from_py(inbound)?;
+        let outbound = LicenseExpression::from_py(outbound)?;
+
+        Ok(self.is_compatible(&inbound, &outbound))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_compatible(compatibility: &Compatibility, inbound: &str, outbound: &str) -> bool {
+        compatibility.is_compatible(
+            &LicenseExpression::parse(inbound).unwrap(),
+            &LicenseExpression::parse(outbound).unwrap(),
+        )
+    }
+
+    #[test]
+    fn builtin_compatibility() {
+        let compatibility = Compatibility::new(Classifier::new().unwrap()).unwrap();
+
+        assert!(is_compatible(&compatibility, "MIT", "Apache-2.0"));
+        assert!(is_compatible(&compatibility, "MIT", "GPL-3.0-only"));
+        assert!(is_compatible(&compatibility, "Apache-2.0", "GPL-3.0-only"));
+        assert!(!is_compatible(&compatibility, "Apache-2.0", "GPL-2.0-only"));
+        assert!(is_compatible(&compatibility, "LGPL-2.1-only", "Apache-2.0"));
+        assert!(!is_compatible(&compatibility, "GPL-3.0-only", "Apache-2.0"));
+        assert!(is_compatible(&compatibility, "GPL-3.0-only", "GPL-3.0-only"));
+        assert!(is_compatible(&compatibility, "GPL-2.0+", "GPL-3.0-only"));
+        assert!(!is_compatible(&compatibility, "GPL-2.0-only", "GPL-3.0-only"));
+        assert!(!is_compatible(&compatibility, "AGPL-3.0-only", "GPL-3.0-only"));
+        assert!(is_compatible(&compatibility, "GPL-3.0-only", "AGPL-3.0-only"));
+        assert!(is_compatible(
+            &compatibility,
+            "GPL-2.0-only WITH Classpath-exception-2.0",
+            "Apache-2.0"
+        ));
+        assert!(!is_compatible(&compatibility, "BUSL-1.1", "MIT"));
+        assert!(is_compatible(&compatibility, "LGPL-2.1-only", "GPL-2.0-only"));
+        assert!(!is_compatible(&compatibility, "LGPL-3.0-only", "GPL-2.0-only"));
+        assert!(is_compatible(&compatibility, "MPL-2.0", "GPL-3.0-only"));
+        assert!(!is_compatible(&compatibility, "EPL-2.0", "GPL-2.0-only"));
+        assert!(!is_compatible(&compatibility, "CDDL-1.0", "GPL-3.0-only"));
+        assert!(!is_compatible(&compatibility, "LicenseRef-unknown", "MIT"));
+    }
+
+    #[test]
+    fn expression_compatibility() {
+        let compatibility = Compatibility::new(Classifier::new().unwrap()).unwrap();
+
+        assert!(is_compatible(&compatibility, "GPL-3.0-only OR MIT", "Apache-2.0"));
+        assert!(!is_compatible(&compatibility, "GPL-3.0-only AND MIT", "Apache-2.0"));
+        assert!(is_compatible(&compatibility, "MIT", "MIT OR Apache-2.0"));
+        assert!(!is_compatible(&compatibility, "GPL-3.0-only", "GPL-3.0-only OR MIT"));
+    }
+
+    #[test]
+    fn override_compatibility() {
+        let mut compatibility = Compatibility::new(Classifier::new().unwrap()).unwrap();
+        compatibility
+            .load("inbound\toutbound\tcompatible\nLicenseRef-foo\t[permissive]\tyes\nMIT\tLicenseRef-bar\tno\n".as_bytes())
+            .unwrap();
+
+        assert!(is_compatible(&compatibility, "LicenseRef-foo", "Apache-2.0"));
+        assert!(!is_compatible(&compatibility, "LicenseRef-foo", "GPL-3.0-only"));
+        assert!(!is_compatible(&compatibility, "MIT", "LicenseRef-bar"));
+
+        // category on both sides is more specific than `*` on one side
+        compatibility
+            .load("inbound\toutbound\tcompatible\nMIT\t*\tno\n[permissive]\tLicenseRef-baz\tyes\n*\tLicenseRef-qux\tno\n".as_bytes())
+            .unwrap();
+        assert!(is_compatible(&compatibility, "MIT", "LicenseRef-baz"));
+        assert!(!is_compatible(&compatibility, "Apache-2.0", "LicenseRef-qux"));
+
+        assert!(compatibility
+            .load("inbound\toutbound\tcompatible\nMIT\tMIT\tmaybe\n".as_bytes())
+            .is_err());
+    }
+}
@@ -0,0 +1,603 @@
+use crate::error::Error;
+
+#[cfg(feature = "python")]
+use pyo3::basic::CompareOp;
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// A license reference in an expression, e.g. `GPL-2.0+` or `DocumentRef-spdx-tool:LicenseRef-foo`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimpleLicense {
+    pub identifier: String,
+    pub or_later: bool,
+    pub document_ref: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ExpressionNode {
+    License(SimpleLicense),
+    With(SimpleLicense, String),
+    And(Vec<ExpressionNode>),
+    Or(Vec<ExpressionNode>),
+}
+
+/// Parsed SPDX license expression.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct LicenseExpression {
+    pub root: ExpressionNode,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    With,
+    Word(String),
+}
+
+impl SimpleLicense {
+    fn parse(word: &str) -> Result<Self, Error> {
+        let (document_ref, license) = match word.split_once(':') {
+            Some((document_ref, license)) if document_ref.starts_with("DocumentRef-") => {
+                (Some(document_ref.to_string()), license)
+            }
+            Some(_) => return Err(Error::LicenseExpression(format!("invalid license reference {}", word))),
+            None => (None, word),
+        };
+
+        let (identifier, or_later) = match license.strip_suffix('+') {
+            Some(identifier) => (identifier, true),
+            None => (license, false),
+        };
+
+        if identifier.is_empty() {
+            return Err(Error::LicenseExpression(format!("invalid license reference {}", word)));
+        }
+
+        Ok(SimpleLicense {
+            identifier: identifier.to_string(),
+            or_later,
+            document_ref,
+        })
+    }
+
+    pub fn is_license_ref(&self) -> bool {
+        self.identifier.starts_with("LicenseRef-")
+    }
+}
+
+impl fmt::Display for SimpleLicense {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(document_ref) = &self.document_ref {
+            write!(f, "{}:", document_ref)?;
+        }
+        write!(f, "{}", self.identifier)?;
+        if self.or_later {
+            write!(f, "+")?;
+        }
+        Ok(())
+    }
+}
+
+impl ExpressionNode {
+    fn licenses<'a>(&'a self, licenses: &mut Vec<&'a SimpleLicense>) {
+        match self {
+            ExpressionNode::License(license) | ExpressionNode::With(license, _) => licenses.push(license),
+            ExpressionNode::And(nodes) | ExpressionNode::Or(nodes) => {
+                for node in nodes {
+                    node.licenses(licenses);
+                }
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    fn exceptions<'a>(&'a self, exceptions: &mut Vec<&'a str>) {
+        match self {
+            ExpressionNode::License(_) => (),
+            ExpressionNode::With(_, exception) => exceptions.push(exception),
+            ExpressionNode::And(nodes) | ExpressionNode::Or(nodes) => {
+                for node in nodes {
+                    node.exceptions(exceptions);
+                }
+            }
+        }
+    }
+
+    fn map_licenses<F: Fn(&SimpleLicense) -> Option<LicenseExpression>>(&self, f: &F) -> ExpressionNode {
+        match self {
+            ExpressionNode::License(license) => match f(license) {
+                Some(replacement) => replacement.root,
+                None => self.clone(),
+            },
+            ExpressionNode::With(license, exception) => match f(license).map(|replacement| replacement.root) {
+                Some(ExpressionNode::License(replacement)) => ExpressionNode::With(replacement, exception.clone()),
+                _ => self.clone(),
+            },
+            ExpressionNode::And(nodes) => {
+                ExpressionNode::And(flatten(nodes.iter().map(|node| node.map_licenses(f)).collect(), false))
+            }
+            ExpressionNode::Or(nodes) => {
+                ExpressionNode::Or(flatten(nodes.iter().map(|node| node.map_licenses(f)).collect(), true))
+            }
+        }
+    }
+
+    fn map_exceptions<F: Fn(&str) -> Option<String>>(&self, f: &F) -> ExpressionNode {
+        match self {
+            ExpressionNode::License(_) => self.clone(),
+            ExpressionNode::With(license, exception) => match f(exception) {
+                Some(replacement) => ExpressionNode::With(license.clone(), replacement),
+                None => self.clone(),
+            },
+            ExpressionNode::And(nodes) => {
+                ExpressionNode::And(nodes.iter().map(|node| node.map_exceptions(f)).collect())
+            }
+            ExpressionNode::Or(nodes) => {
+                ExpressionNode::Or(nodes.iter().map(|node| node.map_exceptions(f)).collect())
+            }
+        }
+    }
+
+    /// Disjunctive normal form: every inner list is one choice of licenses that must all be complied with.
+    fn alternatives(&self) -> Vec<Vec<ExpressionNode>> {
+        match self {
+            ExpressionNode::License(_) | ExpressionNode::With(_, _) => vec![vec![self.clone()]],
+            ExpressionNode::Or(nodes) => nodes.iter().flat_map(|node| node.alternatives()).collect(),
+            ExpressionNode::And(nodes) => nodes.iter().fold(vec![Vec::new()], |acc, node| {
+                let alternatives = node.alternatives();
+                acc.iter()
+                    .flat_map(|left| {
+                        alternatives.iter().map(move |right| {
+                            let mut choice = left.clone();
+                            for term in right {
+                                if !choice.contains(term) {
+                                    choice.push(term.clone());
+                                }
+                            }
+                            choice
+                        })
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ExpressionNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpressionNode::License(license) => write!(f, "{}", license),
+            ExpressionNode::With(license, exception) => write!(f, "{} WITH {}", license, exception),
+            ExpressionNode::And(nodes) | ExpressionNode::Or(nodes) => {
+                let is_and = matches!(self, ExpressionNode::And(_));
+                let operator = if is_and { " AND " } else { " OR " };
+                for (i, node) in nodes.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", operator)?;
+                    }
+                    match node {
+                        ExpressionNode::And(_) | ExpressionNode::Or(_) => write!(f, "({})", node)?,
+                        _ => write!(f, "{}", node)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl LicenseExpression {
+    pub fn parse(expression: &str) -> Result<Self, Error> {
+        let tokens = tokenize(expression);
+        let mut parser = Parser { tokens, position: 0 };
+        let root = parser.parse_or()?;
+
+        if parser.position < parser.tokens.len() {
+            return Err(Error::LicenseExpression(format!(
+                "unexpected token in {:?}",
+                expression
+            )));
+        }
+
+        Ok(LicenseExpression { root })
+    }
+
+    /// Combine expressions with `AND`. Returns `None` for an empty list.
+    pub fn conjunction(expressions: Vec<LicenseExpression>) -> Option<Self> {
+        let mut nodes: Vec<ExpressionNode> = Vec::new();
+        for expression in expressions {
+            match expression.root {
+                ExpressionNode::And(children) => nodes.extend(children),
+                node => nodes.push(node),
+            }
+        }
+
+        match nodes.len() {
+            0 => None,
+            1 => Some(LicenseExpression { root: nodes.remove(0) }),
+            _ => Some(LicenseExpression {
+                root: ExpressionNode::And(nodes),
+            }),
+        }
+    }
+
+    /// Parse every item and combine them with `AND`.
+    pub fn parse_conjunction<S: AsRef<str>>(items: &[S]) -> Result<Option<Self>, Error> {
+        let expressions = items
+            .iter()
+            .filter(|item| !item.as_ref().trim().is_empty())
+            .map(|item| LicenseExpression::parse(item.as_ref()))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(LicenseExpression::conjunction(expressions))
+    }
+
+    /// Replace licenses for which `f` returns `Some`. A license with `WITH` exception is only replaced
+    /// by a single license.
+    pub fn map_licenses<F: Fn(&SimpleLicense) -> Option<LicenseExpression>>(&self, f: &F) -> LicenseExpression {
+        LicenseExpression {
+            root: self.root.map_licenses(f),
+        }
+    }
+
+    /// Replace exceptions of `WITH` for which `f` returns `Some`.
+    pub fn map_exceptions<F: Fn(&str) -> Option<String>>(&self, f: &F) -> LicenseExpression {
+        LicenseExpression {
+            root: self.root.map_exceptions(f),
+        }
+    }
+
+    pub fn simple_licenses(&self) -> Vec<&SimpleLicense> {
+        let mut licenses = Vec::new();
+        self.root.licenses(&mut licenses);
+        licenses
+    }
+
+    /// License identifiers in order of appearance, without duplicates.
+    pub fn license_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for license in self.simple_licenses() {
+            let id = license.to_string();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    pub fn exception_ids(&self) -> Vec<String> {
+        let mut exceptions = Vec::new();
+        self.root.exceptions(&mut exceptions);
+
+        let mut ids: Vec<String> = Vec::new();
+        for exception in exceptions {
+            if !ids.iter().any(|id| id == exception) {
+                ids.push(exception.to_string());
+            }
+        }
+        ids
+    }
+
+    pub fn alternative_ids(&self) -> Vec<Vec<String>> {
+        self.root
+            .alternatives()
+            .iter()
+            .map(|choice| choice.iter().map(|node| node.to_string()).collect())
+            .collect()
+    }
+
+    /// Whether some choice of the `OR` alternatives consists only of allowed licenses.
+    ///
+    /// `X WITH E` is allowed by either `X WITH E` or `X`, and `X+` is allowed by either `X+` or `X`.
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    pub fn is_satisfied_by<S: AsRef<str>>(&self, allowed: &[S]) -> bool {
+        let is_allowed = |id: &str| allowed.iter().any(|a| a.as_ref() == id);
+
+        self.root.alternatives().iter().any(|choice| {
+            choice.iter().all(|node| match node {
+                ExpressionNode::License(license) => {
+                    is_allowed(&license.to_string())
+                        || (license.or_later && is_allowed(&license.identifier))
+                }
+                ExpressionNode::With(license, _) => {
+                    is_allowed(&node.to_string())
+                        || is_allowed(&license.to_string())
+                        || (license.or_later && is_allowed(&license.identifier))
+                }
+                _ => false,
+            })
+        })
+    }
+}
+
+impl fmt::Display for LicenseExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.root)
+    }
+}
+
+impl Serialize for LicenseExpression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "python")]
+impl LicenseExpression {
+    /// Extract a `LicenseExpression` or an expression string.
+    pub fn from_py(value: &PyAny) -> PyResult<Self> {
+        match value.extract::<LicenseExpression>() {
+            Ok(expression) => Ok(expression),
+            Err(_) => {
+                let text: &str = value.extract()?;
+                LicenseExpression::parse(text).map_err(|err| PyValueError::new_err(err.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl LicenseExpression {
+    #[new]
+    fn py_new(expression: &str) -> PyResult<Self> {
+        LicenseExpression::parse(expression).map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// `AND`, `OR`, `WITH` or `None` for a single license.
+    #[getter]
+    fn operator(&self) -> Option<&str> {
+        match self.root {
+            ExpressionNode::License(_) => None,
+            ExpressionNode::With(_, _) => Some("WITH"),
+            ExpressionNode::And(_) => Some("AND"),
+            ExpressionNode::Or(_) => Some("OR"),
+        }
+    }
+
+    fn licenses(&self) -> Vec<String> {
+        self.license_ids()
+    }
+
+    fn exceptions(&self) -> Vec<String> {
+        self.exception_ids()
+    }
+
+    fn alternatives(&self) -> Vec<Vec<String>> {
+        self.alternative_ids()
+    }
+
+    fn satisfies(&self, allowed_list: Vec<String>) -> bool {
+        self.is_satisfied_by(&allowed_list)
+    }
+
+    #[pyo3(name = "to_string")]
+    fn py_to_string(&self) -> String {
+        format!("{}", self)
+    }
+
+    fn __str__(&self) -> String {
+        format!("{}", self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("LicenseExpression({:?})", format!("{}", self))
+    }
+
+    /// Only `==` and `!=` are supported, since expressions have no order.
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python) -> PyObject {
+        match op {
+            CompareOp::Eq => (self == other).into_py(py),
+            CompareOp::Ne => (self != other).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+}
+
+fn tokenize(expression: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+
+    let flush = |word: &mut String, tokens: &mut Vec<Token>| {
+        if word.is_empty() {
+            return;
+        }
+        let token = match word.as_str() {
+            "AND" | "and" => Token::And,
+            "OR" | "or" => Token::Or,
+            "WITH" | "with" => Token::With,
+            _ => Token::Word(word.clone()),
+        };
+        tokens.push(token);
+        word.clear();
+    };
+
+    for c in expression.chars() {
+        match c {
+            '(' | ')' => {
+                flush(&mut word, &mut tokens);
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            c if c.is_whitespace() => flush(&mut word, &mut tokens),
+            c => word.push(c),
+        }
+    }
+    flush(&mut word, &mut tokens);
+
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<ExpressionNode, Error> {
+        let mut nodes = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            nodes.push(self.parse_and()?);
+        }
+
+        Ok(match nodes.len() {
+            1 => nodes.remove(0),
+            _ => ExpressionNode::Or(flatten(nodes, true)),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<ExpressionNode, Error> {
+        let mut nodes = vec![self.parse_with()?];
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            nodes.push(self.parse_with()?);
+        }
+
+        Ok(match nodes.len() {
+            1 => nodes.remove(0),
+            _ => ExpressionNode::And(flatten(nodes, false)),
+        })
+    }
+
+    fn parse_with(&mut self) -> Result<ExpressionNode, Error> {
+        match self.next() {
+            Some(Token::Open) => {
+                let node = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(node),
+                    _ => Err(Error::LicenseExpression("missing closing parenthesis".to_string())),
+                }
+            }
+            Some(Token::Word(word)) => {
+                let license = SimpleLicense::parse(&word)?;
+                if self.peek() != Some(&Token::With) {
+                    return Ok(ExpressionNode::License(license));
+                }
+
+                self.position += 1;
+                match self.next() {
+                    Some(Token::Word(exception)) => Ok(ExpressionNode::With(license, exception)),
+                    _ => Err(Error::LicenseExpression(format!(
+                        "missing exception after {} WITH",
+                        license
+                    ))),
+                }
+            }
+            Some(token) => Err(Error::LicenseExpression(format!("unexpected token {:?}", token))),
+            None => Err(Error::LicenseExpression("unexpected end of expression".to_string())),
+        }
+    }
+}
+
+fn flatten(nodes: Vec<ExpressionNode>, is_or: bool) -> Vec<ExpressionNode> {
+    let mut flattened = Vec::new();
+    for node in nodes {
+        match node {
+            ExpressionNode::Or(children) if is_or => flattened.extend(children),
+            ExpressionNode::And(children) if !is_or => flattened.extend(children),
+            node => flattened.push(node),
+        }
+    }
+    flattened
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_simple() {
+        let expression = LicenseExpression::parse("MIT").unwrap();
+        assert_eq!(expression.license_ids(), vec!["MIT"]);
+        assert_eq!(expression.to_string(), "MIT");
+    }
+
+    #[test]
+    fn parse_or_and_precedence() {
+        let expression = LicenseExpression::parse("MIT or Apache-2.0 AND BSD-3-Clause").unwrap();
+        assert_eq!(expression.to_string(), "MIT OR (Apache-2.0 AND BSD-3-Clause)");
+        assert_eq!(
+            expression.alternative_ids(),
+            vec![vec!["MIT"], vec!["Apache-2.0", "BSD-3-Clause"]]
+        );
+    }
+
+    #[test]
+    fn parse_parenthesis() {
+        let expression = LicenseExpression::parse("(MIT OR Apache-2.0) AND BSD-3-Clause").unwrap();
+        assert_eq!(expression.to_string(), "(MIT OR Apache-2.0) AND BSD-3-Clause");
+        assert_eq!(
+            expression.alternative_ids(),
+            vec![vec!["MIT", "BSD-3-Clause"], vec!["Apache-2.0", "BSD-3-Clause"]]
+        );
+    }
+
+    #[test]
+    fn parse_with_and_or_later() {
+        let expression = LicenseExpression::parse("GPL-2.0+ WITH Classpath-exception-2.0").unwrap();
+        assert_eq!(expression.license_ids(), vec!["GPL-2.0+"]);
+        assert_eq!(expression.exception_ids(), vec!["Classpath-exception-2.0"]);
+        assert_eq!(expression.to_string(), "GPL-2.0+ WITH Classpath-exception-2.0");
+    }
+
+    #[test]
+    fn parse_license_ref() {
+        let expression =
+            LicenseExpression::parse("DocumentRef-spdx-tool:LicenseRef-foo AND LicenseRef-bar").unwrap();
+        let licenses = expression.simple_licenses();
+        assert_eq!(licenses[0].document_ref, Some("DocumentRef-spdx-tool".to_string()));
+        assert!(licenses[0].is_license_ref());
+        assert_eq!(
+            expression.license_ids(),
+            vec!["DocumentRef-spdx-tool:LicenseRef-foo", "LicenseRef-bar"]
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(LicenseExpression::parse("").is_err());
+        assert!(LicenseExpression::parse("MIT AND").is_err());
+        assert!(LicenseExpression::parse("(MIT").is_err());
+        assert!(LicenseExpression::parse("Apache 2.0").is_err());
+    }
+
+    #[test]
+    fn satisfies() {
+        let allowed = vec!["MIT", "GPL-2.0"];
+
+        assert!(LicenseExpression::parse("MIT OR GPL-3.0-only").unwrap().is_satisfied_by(&allowed));
+        assert!(!LicenseExpression::parse("MIT AND GPL-3.0-only").unwrap().is_satisfied_by(&allowed));
+        assert!(LicenseExpression::parse("GPL-2.0+").unwrap().is_satisfied_by(&allowed));
+        assert!(LicenseExpression::parse("GPL-2.0 WITH Classpath-exception-2.0")
+            .unwrap()
+            .is_satisfied_by(&allowed));
+    }
+
+    #[test]
+    fn conjunction() {
+        let expression = LicenseExpression::parse_conjunction(&["MIT", "Apache-2.0 OR BSD-3-Clause"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(expression.to_string(), "MIT AND (Apache-2.0 OR BSD-3-Clause)");
+        assert_eq!(LicenseExpression::parse_conjunction::<&str>(&[]).unwrap(), None);
+    }
+}
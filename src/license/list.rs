@@ -0,0 +1,187 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const LICENSES_JSON: &str = include_str!("../../data/spdx/licenses.json");
+const EXCEPTIONS_JSON: &str = include_str!("../../data/spdx/exceptions.json");
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct LicenseInfo {
+    #[serde(rename = "licenseId")]
+    pub id: String,
+    pub name: String,
+
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    #[serde(default)]
+    pub is_osi_approved: bool,
+
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    #[serde(default)]
+    pub is_fsf_libre: bool,
+
+    #[serde(rename = "isDeprecatedLicenseId", default)]
+    pub is_deprecated: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct ExceptionInfo {
+    #[serde(rename = "licenseExceptionId")]
+    pub id: String,
+
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    #[serde(rename = "isDeprecatedLicenseId", default)]
+    pub is_deprecated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LicensesJson {
+    license_list_version: String,
+    licenses: Vec<LicenseInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExceptionsJson {
+    exceptions: Vec<ExceptionInfo>,
+}
+
+/// The SPDX license list embedded in the binary.
+#[derive(Debug)]
+pub struct LicenseList {
+    #[cfg_attr(not(feature = "python"), allow(dead_code))]
+    pub version: String,
+    pub licenses: Vec<LicenseInfo>,
+    pub exceptions: Vec<ExceptionInfo>,
+    license_index: HashMap<String, usize>,
+    exception_index: HashMap<String, usize>,
+}
+
+impl LicenseList {
+    /// Find license by SPDX identifier. Identifiers are matched case-insensitively.
+    pub fn get(&self, id: &str) -> Option<&LicenseInfo> {
+        self.license_index
+            .get(&id.to_lowercase())
+            .map(|&i| &self.licenses[i])
+    }
+
+    /// Find license exception by SPDX identifier. Identifiers are matched case-insensitively.
+    pub fn get_exception(&self, id: &str) -> Option<&ExceptionInfo> {
+        self.exception_index
+            .get(&id.to_lowercase())
+            .map(|&i| &self.exceptions[i])
+    }
+}
+
+pub fn license_list() -> &'static LicenseList {
+    static LICENSE_LIST: OnceLock<LicenseList> = OnceLock::new();
+
+    LICENSE_LIST.get_or_init(|| {
+        let licenses: LicensesJson = serde_json::from_str(LICENSES_JSON).expect("embedded licenses.json is invalid");
+        let exceptions: ExceptionsJson =
+            serde_json::from_str(EXCEPTIONS_JSON).expect("embedded exceptions.json is invalid");
+
+        let license_index = licenses
+            .licenses
+            .iter()
+            .enumerate()
+            .map(|(i, license)| (license.id.to_lowercase(), i))
+            .collect();
+        let exception_index = exceptions
+            .exceptions
+            .iter()
+            .enumerate()
+            .map(|(i, exception)| (exception.id.to_lowercase(), i))
+            .collect();
+
+        LicenseList {
+            version: licenses.license_list_version,
+            licenses: licenses.licenses,
+            exceptions: exceptions.exceptions,
+            license_index,
+            exception_index,
+        }
+    })
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl LicenseInfo {
+    #[getter]
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[getter]
+    fn is_osi_approved(&self) -> bool {
+        self.is_osi_approved
+    }
+
+    #[getter]
+    fn is_fsf_libre(&self) -> bool {
+        self.is_fsf_libre
+    }
+
+    #[getter]
+    fn is_deprecated(&self) -> bool {
+        self.is_deprecated
+    }
+
+    fn __repr__(&self) -> String {
+        format!("LicenseInfo({:?})", self.id)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ExceptionInfo {
+    #[getter]
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    #[getter]
+    fn is_deprecated(&self) -> bool {
+        self.is_deprecated
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ExceptionInfo({:?})", self.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_license() {
+        let list = license_list();
+
+        let mit = list.get("MIT").unwrap();
+        assert_eq!(mit.name, "MIT License");
+        assert!(mit.is_osi_approved);
+        assert!(!mit.is_deprecated);
+
+        assert!(list.get("gpl-2.0").unwrap().is_deprecated);
+        assert!(list.get("UNKNOWN").is_none());
+    }
+
+    #[test]
+    fn get_exception() {
+        let list = license_list();
+
+        assert!(list.get_exception("Classpath-exception-2.0").is_some());
+        assert!(list.get_exception("MIT").is_none());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod category;
+pub mod compatibility;
+mod expression;
+pub mod list;
+pub mod normalize;
+
+pub use expression::{LicenseExpression, SimpleLicense};
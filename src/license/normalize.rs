@@ -0,0 +1,260 @@
+use crate::error::Error;
+use crate::license::list::license_list;
+use crate::license::{LicenseExpression, SimpleLicense};
+use crate::model::material::Material;
+
+use csv::ReaderBuilder;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Read;
+
+const BUILTIN_ALIASES_TSV: &str = include_str!("../../data/license-aliases.tsv");
+
+/// Annotation key recording licenses before normalization.
+pub const ORIGINAL_LICENSES_ANNOTATION: &str = "original-licenses";
+
+#[derive(Debug, Deserialize)]
+struct AliasTsv {
+    alias: String,
+    license: String,
+}
+
+/// Maps aliases, case variants, license names and deprecated identifiers to canonical SPDX identifiers.
+#[derive(Debug)]
+pub struct Normalizer {
+    user_aliases: HashMap<String, String>,
+    builtin_aliases: HashMap<String, String>,
+    names: HashMap<String, String>,
+}
+
+impl Normalizer {
+    pub fn new() -> Result<Self, Error> {
+        let mut names: HashMap<String, String> = HashMap::new();
+        for license in &license_list().licenses {
+            let key = alias_key(&license.name);
+            // prefer non-deprecated identifier when several identifiers share the same name
+            if !license.is_deprecated || !names.contains_key(&key) {
+                names.insert(key, license.id.clone());
+            }
+        }
+
+        Ok(Normalizer {
+            user_aliases: HashMap::new(),
+            builtin_aliases: load_aliases(BUILTIN_ALIASES_TSV.as_bytes())?,
+            names,
+        })
+    }
+
+    /// Load user alias table. User aliases take precedence over everything else.
+    pub fn load_user_aliases<R: Read>(&mut self, source: R) -> Result<(), Error> {
+        self.user_aliases.extend(load_aliases(source)?);
+        Ok(())
+    }
+
+    /// Returns canonical license expression string for a license, or `None` if it is not changed.
+    pub fn normalize(&self, license: &str) -> Option<String> {
+        let normalized = self.lookup(license)?;
+        match normalized == license {
+            true => None,
+            false => Some(normalized),
+        }
+    }
+
+    fn lookup(&self, license: &str) -> Option<String> {
+        let key = alias_key(license);
+        let list = license_list();
+
+        if let Some(alias) = self.user_aliases.get(&key) {
+            return Some(alias.clone());
+        }
+
+        let info = list.get(license.trim());
+        if let Some(info) = info {
+            if !info.is_deprecated {
+                return Some(info.id.clone());
+            }
+        }
+
+        if let Some(alias) = self.builtin_aliases.get(&key) {
+            return Some(alias.clone());
+        }
+
+        if let Some(id) = self.names.get(&key) {
+            return Some(id.clone());
+        }
+
+        if let Some(info) = info {
+            return Some(info.id.clone());
+        }
+
+        // e.g. apache-1.0+
+        if let Some(base) = license.trim().strip_suffix('+') {
+            if let Some(info) = list.get(base) {
+                return Some(format!("{}+", info.id));
+            }
+        }
+
+        None
+    }
+
+    fn normalize_simple_license(&self, license: &SimpleLicense) -> Option<LicenseExpression> {
+        if license.document_ref.is_some() || license.is_license_ref() {
+            return None;
+        }
+
+        let normalized = self.normalize(&license.to_string())?;
+        LicenseExpression::parse(&normalized).ok()
+    }
+
+    fn normalize_exception(&self, exception: &str) -> Option<String> {
+        let info = license_list().get_exception(exception)?;
+        (info.id != exception).then(|| info.id.clone())
+    }
+
+    fn normalize_expression(&self, expression: &LicenseExpression) -> LicenseExpression {
+        expression
+            .map_licenses(&|license| self.normalize_simple_license(license))
+            .map_exceptions(&|exception| self.normalize_exception(exception))
+    }
+
+    /// Parse a license expression given by user (e.g. `--project-license`) and normalize it in the same way as
+    /// materials. A license name such as `Apache 2.0` is accepted as a whole.
+    pub fn parse_expression(&self, expression: &str) -> Result<LicenseExpression, Error> {
+        let expression = self.normalize(expression).unwrap_or_else(|| expression.to_string());
+        let parsed = LicenseExpression::parse(&expression)?;
+
+        Ok(self.normalize_expression(&parsed))
+    }
+
+    /// Normalize licenses of material. The original licenses are recorded in `original-licenses` annotation.
+    pub fn normalize_material(&self, material: &mut Material) {
+        let original = match &material.license_expression {
+            Some(expression) => expression.to_string(),
+            None => material.licenses.join(","),
+        };
+
+        match &material.license_expression {
+            Some(expression) => {
+                let normalized = self.normalize_expression(expression);
+                material.set_expression(normalized);
+            }
+            None => {
+                let licenses = material
+                    .licenses
+                    .iter()
+                    .map(|license| self.normalize(license).unwrap_or_else(|| license.clone()))
+                    .collect();
+                material.set_license_strings(licenses);
+            }
+        }
+
+        let normalized = match &material.license_expression {
+            Some(expression) => expression.to_string(),
+            None => material.licenses.join(","),
+        };
+
+        if normalized != original {
+            material
+                .annotations
+                .insert(ORIGINAL_LICENSES_ANNOTATION.to_string(), original);
+        }
+    }
+}
+
+fn alias_key(alias: &str) -> String {
+    alias.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn load_aliases<R: Read>(source: R) -> Result<HashMap<String, String>, Error> {
+    let mut aliases: HashMap<String, String> = HashMap::new();
+
+    let mut tsv_reader = ReaderBuilder::new()
+        .has_headers(true)
+        .delimiter(b'\t')
+        .comment(Some(b'#'))
+        .from_reader(source);
+    for row in tsv_reader.deserialize() {
+        let tsv: AliasTsv = row?;
+        aliases.insert(alias_key(&tsv.alias), tsv.license.trim().to_string());
+    }
+
+    Ok(aliases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_license() {
+        let normalizer = Normalizer::new().unwrap();
+
+        assert_eq!(normalizer.normalize("MIT"), None);
+        assert_eq!(normalizer.normalize("apache-2.0"), Some("Apache-2.0".to_string()));
+        assert_eq!(normalizer.normalize("Apache 2.0"), Some("Apache-2.0".to_string()));
+        assert_eq!(normalizer.normalize("MIT License"), Some("MIT".to_string()));
+        assert_eq!(normalizer.normalize("GPLv2+"), Some("GPL-2.0-or-later".to_string()));
+        assert_eq!(normalizer.normalize("GPL-2.0+"), Some("GPL-2.0-or-later".to_string()));
+        assert_eq!(
+            normalizer.normalize("GNU General Public License v2.0 or later"),
+            Some("GPL-2.0-or-later".to_string())
+        );
+        assert_eq!(normalizer.normalize("UNKNOWN"), None);
+    }
+
+    #[test]
+    fn parse_expression() {
+        let normalizer = Normalizer::new().unwrap();
+
+        let parse = |expression: &str| normalizer.parse_expression(expression).unwrap().to_string();
+        assert_eq!(parse("GPL-2.0"), "GPL-2.0-only");
+        assert_eq!(parse("Apache 2.0"), "Apache-2.0");
+        assert_eq!(parse("GPL-2.0 OR mit"), "GPL-2.0-only OR MIT");
+        assert!(normalizer.parse_expression("MIT AND").is_err());
+    }
+
+    #[test]
+    fn normalize_material() {
+        let normalizer = Normalizer::new().unwrap();
+
+        let mut material = Material::default();
+        material.set_license_strings(vec!["Apache 2.0".to_string(), "mit".to_string()]);
+        normalizer.normalize_material(&mut material);
+        assert_eq!(material.licenses, vec!["Apache-2.0", "MIT"]);
+        assert_eq!(
+            material.annotations.get(ORIGINAL_LICENSES_ANNOTATION),
+            Some(&"Apache 2.0,mit".to_string())
+        );
+
+        let mut material = Material::default();
+        material.set_license_strings(vec!["GPL-2.0+ OR MIT".to_string()]);
+        normalizer.normalize_material(&mut material);
+        assert_eq!(
+            material.license_expression.unwrap().to_string(),
+            "GPL-2.0-or-later OR MIT"
+        );
+
+        let mut material = Material::default();
+        material.set_license_strings(vec!["gpl-2.0-or-later with classpath-exception-2.0".to_string()]);
+        normalizer.normalize_material(&mut material);
+        assert_eq!(
+            material.license_expression.unwrap().to_string(),
+            "GPL-2.0-or-later WITH Classpath-exception-2.0"
+        );
+
+        let mut material = Material::default();
+        material.set_license_strings(vec!["MIT".to_string()]);
+        normalizer.normalize_material(&mut material);
+        assert!(material.annotations.is_empty());
+    }
+
+    #[test]
+    fn user_aliases() {
+        let mut normalizer = Normalizer::new().unwrap();
+        normalizer
+            .load_user_aliases("alias\tlicense\nMy License\tLicenseRef-my\n".as_bytes())
+            .unwrap();
+
+        assert_eq!(normalizer.normalize("my license"), Some("LicenseRef-my".to_string()));
+    }
+}
@@ -1,8 +1,19 @@
+// pyo3 0.16 #[pymethods] expands to impl blocks that trip this lint on recent compilers.
+#![allow(non_local_definitions)]
+
+mod cargo;
 mod cli;
 mod command;
+mod curation;
 mod cyclonedx;
 mod error;
+mod license;
 mod model;
+mod npm;
+mod policy;
+mod spdx;
+#[cfg(feature = "python")]
+mod python;
 
 use clap::Parser;
 
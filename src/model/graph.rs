@@ -0,0 +1,240 @@
+use crate::model::material::Material;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Paths from roots more than this are not recorded on material.
+const MAX_PATHS_FROM_ROOT: usize = 10;
+
+/// Dependency graph of SBOM elements identified by `bom-ref` of CycloneDX or `SPDXID` of SPDX.
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    dependencies: HashMap<String, Vec<String>>,
+    dependents: HashMap<String, Vec<String>>,
+    roots: Vec<String>,
+}
+
+impl DependencyGraph {
+    pub fn add_root(&mut self, id: &str) {
+        if !self.roots.iter().any(|root| root == id) {
+            self.roots.push(id.to_string());
+        }
+    }
+
+    /// `from` depends on `to`.
+    pub fn add_dependency(&mut self, from: &str, to: &str) {
+        if from == to {
+            return;
+        }
+
+        let dependencies = self.dependencies.entry(from.to_string()).or_default();
+        if dependencies.iter().any(|d| d == to) {
+            return;
+        }
+        dependencies.push(to.to_string());
+        self.dependents
+            .entry(to.to_string())
+            .or_default()
+            .push(from.to_string());
+    }
+
+    fn is_empty(&self) -> bool {
+        self.dependencies.is_empty()
+    }
+
+    /// Declared roots, or elements that nothing depends on if no root is declared.
+    fn root_ids(&self) -> HashSet<&str> {
+        let declared = self
+            .roots
+            .iter()
+            .filter(|root| self.dependencies.contains_key(*root) || self.dependents.contains_key(*root))
+            .map(|root| root.as_str())
+            .collect::<HashSet<_>>();
+        if !declared.is_empty() {
+            return declared;
+        }
+
+        self.dependencies
+            .keys()
+            .filter(|id| !self.dependents.contains_key(*id))
+            .map(|id| id.as_str())
+            .collect()
+    }
+
+    /// Set `is_direct`, `dependencies`, `dependents` and `paths_from_root` of materials. They are ids of elements,
+    /// since names are not unique in SBOM (e.g. different versions of a package).
+    ///
+    /// Materials without `id` are left unchanged.
+    pub fn apply(&self, materials: &mut [Material]) {
+        if self.is_empty() {
+            return;
+        }
+
+        let roots = self.root_ids();
+        let shortest_paths = self.shortest_paths(&roots);
+
+        for material in materials.iter_mut() {
+            let id = match &material.id {
+                Some(id) => id.clone(),
+                None => continue,
+            };
+
+            let dependents = self.dependents.get(&id).cloned().unwrap_or_default();
+            material.is_direct = Some(dependents.iter().any(|d| roots.contains(d.as_str())));
+            material.dependencies = self.dependencies.get(&id).cloned().unwrap_or_default();
+            material.dependents = dependents;
+            material.paths_from_root = self.paths_from_root(&id, &roots, &shortest_paths);
+        }
+    }
+
+    /// Shortest path from roots to each reachable element, found by a breadth-first search from all roots.
+    fn shortest_paths<'a>(&'a self, roots: &HashSet<&'a str>) -> HashMap<&'a str, Vec<&'a str>> {
+        let mut sorted_roots = roots.iter().copied().collect::<Vec<_>>();
+        sorted_roots.sort_unstable();
+
+        let mut paths: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        for root in sorted_roots {
+            paths.insert(root, vec![root]);
+            queue.push_back(root);
+        }
+
+        while let Some(current) = queue.pop_front() {
+            for dependency in self.dependencies.get(current).into_iter().flatten() {
+                if paths.contains_key(dependency.as_str()) {
+                    continue;
+                }
+                let mut path = paths[current].clone();
+                path.push(dependency);
+                paths.insert(dependency, path);
+                queue.push_back(dependency);
+            }
+        }
+
+        paths
+    }
+
+    /// Paths from roots to the element, shortest first. One shortest path is taken through each dependent, so
+    /// that the paths show how the element is introduced without enumerating all paths.
+    fn paths_from_root(
+        &self,
+        id: &str,
+        roots: &HashSet<&str>,
+        shortest_paths: &HashMap<&str, Vec<&str>>,
+    ) -> Vec<Vec<String>> {
+        if roots.contains(id) {
+            return vec![vec![id.to_string()]];
+        }
+
+        let mut paths = self
+            .dependents
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|dependent| shortest_paths.get(dependent.as_str()))
+            // skip cycles
+            .filter(|path| !path.contains(&id))
+            .map(|path| path.iter().chain([&id]).map(|id| id.to_string()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        paths.sort_by_key(|path| path.len());
+        paths.dedup();
+        paths.truncate(MAX_PATHS_FROM_ROOT);
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn material(id: &str) -> Material {
+        Material {
+            name: id.to_string(),
+            id: Some(id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn apply_graph() {
+        let mut graph = DependencyGraph::default();
+        graph.add_root("root");
+        graph.add_dependency("root", "a");
+        graph.add_dependency("root", "b");
+        graph.add_dependency("a", "c");
+        graph.add_dependency("b", "a");
+        graph.add_dependency("c", "a"); // cycle
+
+        let app = Material {
+            name: "app".to_string(),
+            id: Some("root".to_string()),
+            ..Default::default()
+        };
+        let mut materials = vec![app, material("a"), material("b"), material("c"), material("d")];
+        graph.apply(&mut materials);
+
+        let [_, a, b, c, d] = &materials[..] else { panic!() };
+        assert_eq!(a.is_direct, Some(true));
+        assert_eq!(a.dependencies, vec!["c"]);
+        assert_eq!(a.dependents, vec!["root", "b", "c"]);
+        assert_eq!(a.paths_from_root, vec![vec!["root", "a"], vec!["root", "b", "a"]]);
+        assert_eq!(b.is_direct, Some(true));
+        assert_eq!(c.is_direct, Some(false));
+        assert_eq!(c.paths_from_root, vec![vec!["root", "a", "c"]]);
+        assert_eq!(d.is_direct, Some(false));
+        assert!(d.paths_from_root.is_empty());
+    }
+
+    #[test]
+    fn roots_without_declaration() {
+        let mut graph = DependencyGraph::default();
+        graph.add_dependency("a", "b");
+
+        let mut materials = vec![material("a"), material("b")];
+        graph.apply(&mut materials);
+
+        assert_eq!(materials[0].is_direct, Some(false));
+        assert_eq!(materials[0].paths_from_root, vec![vec!["a"]]);
+        assert_eq!(materials[1].is_direct, Some(true));
+        assert_eq!(materials[1].paths_from_root, vec![vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn ids_of_same_name() {
+        let mut graph = DependencyGraph::default();
+        graph.add_root("app");
+        graph.add_dependency("app", "foo@1");
+        graph.add_dependency("app", "bar");
+        graph.add_dependency("bar", "foo@2");
+
+        let foo = |id: &str| Material {
+            name: "foo".to_string(),
+            id: Some(id.to_string()),
+            ..Default::default()
+        };
+        let mut materials = vec![material("app"), foo("foo@1"), material("bar"), foo("foo@2")];
+        graph.apply(&mut materials);
+
+        assert_eq!(materials[0].dependencies, vec!["foo@1", "bar"]);
+        assert_eq!(materials[3].paths_from_root, vec![vec!["app", "bar", "foo@2"]]);
+    }
+
+    #[test]
+    fn many_paths() {
+        // chain of diamonds has 2^n paths from root
+        let mut graph = DependencyGraph::default();
+        graph.add_root("n0");
+        for i in 0..64 {
+            graph.add_dependency(&format!("n{}", i), &format!("l{}", i));
+            graph.add_dependency(&format!("n{}", i), &format!("r{}", i));
+            graph.add_dependency(&format!("l{}", i), &format!("n{}", i + 1));
+            graph.add_dependency(&format!("r{}", i), &format!("n{}", i + 1));
+        }
+
+        let mut materials = vec![material("n64")];
+        graph.apply(&mut materials);
+
+        assert_eq!(materials[0].paths_from_root.len(), 2);
+        assert_eq!(materials[0].paths_from_root[0].len(), 129);
+    }
+}
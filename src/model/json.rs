@@ -0,0 +1,73 @@
+use serde::de::{Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde_json::Value;
+use std::fmt;
+use std::io::{BufReader, Read};
+
+/// The first of `keys` found in the top level object of JSON, and its value.
+///
+/// Reading stops at the key, and the values before it are skipped without being built, so that the format of a large
+/// JSON is sniffed without loading it.
+pub fn find_top_level_value<R: Read>(reader: R, keys: &[&str]) -> Option<(String, Value)> {
+    struct KeyVisitor<'a> {
+        keys: &'a [&'a str],
+        found: &'a mut Option<(String, Value)>,
+    }
+
+    impl<'de> Visitor<'de> for KeyVisitor<'_> {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a JSON object")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+            while let Some(key) = map.next_key::<String>()? {
+                if self.keys.contains(&key.as_str()) {
+                    let value = map.next_value::<Value>()?;
+                    *self.found = Some((key, value));
+                    return Ok(());
+                }
+                map.next_value::<IgnoredAny>()?;
+            }
+            Ok(())
+        }
+    }
+
+    let mut found = None;
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(reader));
+    // the rest of the object is left unread, which is reported as an error of the unclosed object
+    let _ = deserializer.deserialize_map(KeyVisitor {
+        keys,
+        found: &mut found,
+    });
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_first_key() {
+        let json = r#"{"packages": [{"name": "foo"}], "workspace_members": ["foo"], "@context": "x"}"#;
+        assert_eq!(
+            find_top_level_value(json.as_bytes(), &["@context", "workspace_members"]),
+            Some(("workspace_members".to_string(), serde_json::json!(["foo"])))
+        );
+
+        // nested keys are not top level
+        assert_eq!(find_top_level_value(r#"{"a": {"@context": "x"}}"#.as_bytes(), &["@context"]), None);
+        assert_eq!(find_top_level_value("name\tversion".as_bytes(), &["@context"]), None);
+    }
+
+    #[test]
+    fn stop_reading_at_key() {
+        // the rest is not read, even if it is broken
+        let json = r#"{"@context": "https://spdx.org/rdf/3.0.1/spdx-context.jsonld", "@graph": [{"#;
+        assert_eq!(
+            find_top_level_value(json.as_bytes(), &["@context"]).map(|(key, _)| key),
+            Some("@context".to_string())
+        );
+    }
+}
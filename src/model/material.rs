@@ -1,39 +1,461 @@
-use crate::cli::SourceType;
-use crate::cyclonedx::{load_json_file, load_xml_file, Bom, Components, LicenseChoice, Licenses};
+use crate::cargo::{load_metadata, Metadata};
+use crate::cli::{EffectiveLicenses, SourceType, SpdxLicenseSource};
+use crate::cyclonedx::{load_json_file, load_protobuf_file, load_xml_file, Bom, Component, Components, LicenseChoice, Licenses};
 use crate::error::Error;
+use crate::license::LicenseExpression;
+use crate::model::graph::DependencyGraph;
+use crate::npm::{load_installed_package_json, load_package_lock, PackageLock};
+#[cfg(feature = "python")]
+use crate::model::purl::PackageUrl;
+use crate::spdx::{load_rdf_file, load_spdx3_json_file, load_xlsx_file, load_yaml_file};
 
 use csv::ReaderBuilder;
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
-use spdx_rs::models::{SPDX, SpdxExpression};
+use spdx_rs::models::{RelationshipType, SPDX};
 use spdx_rs::parsers::spdx_from_tag_value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs::File;
 use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Serialize, Clone)]
-#[pyclass]
+#[derive(Debug, Serialize, Clone, Default)]
+#[cfg_attr(feature = "python", pyclass)]
 pub struct Material {
-    #[pyo3(get, set)]
     pub name: String,
-
-    #[pyo3(get, set)]
     pub version: Option<String>,
+    pub purl: Option<String>,
+    pub group: Option<String>,
+    pub supplier: Option<String>,
 
-    #[pyo3(get, set)]
-    pub licenses: Vec<String>,
+    /// Component type (e.g. `library`) of CycloneDX or primary package purpose of SPDX in lower case.
+    #[serde(rename = "type")]
+    pub material_type: Option<String>,
+
+    /// `bom-ref` of CycloneDX or `SPDXID` of SPDX.
+    pub id: Option<String>,
+
+    /// Scope of CycloneDX component (`required`, `optional` or `excluded`), or the scope given by dependency kinds of
+    /// Cargo and flags of npm. `None` means `required`.
+    pub scope: Option<String>,
+
+    /// Kinds of Cargo dependency (`normal`, `build` or `dev`) by which the material is reached from workspace members.
+    pub dependency_kinds: Vec<String>,
+
+    /// Path of SPDX file, or the file of SPDX snippet.
+    pub file_path: Option<String>,
+
+    /// Path of the license file given instead of or in addition to the license, e.g. `license-file` of Cargo.
+    pub license_file: Option<String>,
+
+    /// Id of the material containing this material, e.g. the package of SPDX file or the component of nested
+    /// CycloneDX component.
+    pub parent: Option<String>,
+
+    /// Ids of materials contained by this material.
+    pub children: Vec<String>,
+
+    /// Whether the material is the root component of SBOM (CycloneDX `metadata.component`) or the project of
+    /// `package-lock.json`, i.e. the project.
+    pub is_project: bool,
+
+    pub cpe: Option<String>,
+    pub author: Option<String>,
+    pub publisher: Option<String>,
+    pub copyright: Option<String>,
+
+    /// Hash algorithm (e.g. `SHA-256`) to hash value.
+    pub hashes: HashMap<String, String>,
+
+    pub external_references: Vec<ExternalReference>,
+
+    /// Licenses declared by the authors: CycloneDX licenses acknowledged as `declared` (or not acknowledged), SPDX
+    /// `PackageLicenseDeclared`. Strings are licenses or SPDX expressions as written in SBOM.
+    pub declared_licenses: Vec<String>,
+
+    /// Licenses concluded by the SBOM creator: CycloneDX licenses acknowledged as `concluded`, SPDX
+    /// `PackageLicenseConcluded`.
+    pub concluded_licenses: Vec<String>,
 
-    #[pyo3(get, set)]
+    /// Licenses detected in files, e.g. by scanners: CycloneDX `evidence.licenses`, SPDX `PackageLicenseInfoFromFiles`.
+    pub detected_licenses: Vec<String>,
+
+    /// Effective licenses selected by `--effective-licenses`.
+    pub licenses: Vec<String>,
+    pub license_expression: Option<LicenseExpression>,
+    pub license_categories: HashMap<String, String>,
     pub annotations: HashMap<String, String>,
+
+    /// `None` if SBOM does not have dependency graph.
+    pub is_direct: Option<bool>,
+
+    /// Ids of materials this material depends on.
+    pub dependencies: Vec<String>,
+
+    /// Ids of materials that depend on this material.
+    pub dependents: Vec<String>,
+
+    /// Ids of materials from root to this material.
+    pub paths_from_root: Vec<Vec<String>>,
+
+    /// Reasons given by curations. They are reported with the changes of curation, not as part of material.
+    #[serde(skip)]
+    pub curation_reasons: Vec<String>,
+}
+
+/// A reference to external resource of material, e.g. `vcs` or `website` of CycloneDX.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct ExternalReference {
+    #[serde(rename = "type")]
+    pub reference_type: String,
+    pub url: String,
+    pub comment: Option<String>,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ExternalReference {
+    #[getter(type)]
+    fn py_type(&self) -> String {
+        self.reference_type.clone()
+    }
+
+    #[getter]
+    fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    #[getter]
+    fn comment(&self) -> Option<String> {
+        self.comment.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ExternalReference(type={:?}, url={:?})", self.reference_type, self.url)
+    }
 }
 
+impl Material {
+    /// Set licenses from license strings, parsing each of them as SPDX expression combined with `AND`.
+    ///
+    /// When any string is not a valid expression (e.g. `Apache 2.0`), the strings are kept as is and
+    /// `license_expression` is `None`.
+    pub fn set_license_strings(&mut self, licenses: Vec<String>) {
+        match LicenseExpression::parse_conjunction(&licenses) {
+            Ok(Some(expression)) => self.set_expression(expression),
+            _ => {
+                self.licenses = licenses;
+                self.license_expression = None;
+            }
+        }
+    }
+
+    pub fn set_expression(&mut self, expression: LicenseExpression) {
+        self.licenses = expression.license_ids();
+        self.license_expression = Some(expression);
+    }
+
+    /// Replace effective licenses by declared, concluded or detected licenses. `Auto` keeps licenses chosen by loader.
+    pub fn select_licenses(&mut self, source: &EffectiveLicenses) {
+        let licenses = match source {
+            EffectiveLicenses::Auto => return,
+            EffectiveLicenses::Declared => self.declared_licenses.clone(),
+            EffectiveLicenses::Concluded => self.concluded_licenses.clone(),
+            EffectiveLicenses::Detected => self.detected_licenses.clone(),
+        };
+
+        self.set_license_strings(licenses);
+    }
+}
+
+#[cfg(feature = "python")]
 #[pymethods]
 impl Material {
+    #[getter]
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[setter]
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    #[getter]
+    fn version(&self) -> Option<String> {
+        self.version.clone()
+    }
+
+    #[setter]
+    fn set_version(&mut self, version: Option<String>) {
+        self.version = version;
+    }
+
+    /// `None` if purl is not set or invalid.
+    #[getter]
+    fn purl(&self) -> Option<PackageUrl> {
+        self.purl.as_ref().and_then(|purl| PackageUrl::parse(purl).ok())
+    }
+
+    /// Accepts a `PackageUrl`, a purl string or `None`.
+    #[setter]
+    fn set_purl(&mut self, purl: Option<&PyAny>) -> PyResult<()> {
+        self.purl = match purl {
+            None => None,
+            Some(value) => match value.extract::<PackageUrl>() {
+                Ok(purl) => Some(purl.to_string()),
+                Err(_) => {
+                    let text: &str = value.extract()?;
+                    PackageUrl::parse(text).map_err(|err| PyValueError::new_err(err.to_string()))?;
+                    Some(text.to_string())
+                }
+            },
+        };
+
+        Ok(())
+    }
+
+    #[getter]
+    fn group(&self) -> Option<String> {
+        self.group.clone()
+    }
+
+    #[setter]
+    fn set_group(&mut self, group: Option<String>) {
+        self.group = group;
+    }
+
+    #[getter]
+    fn supplier(&self) -> Option<String> {
+        self.supplier.clone()
+    }
+
+    #[setter]
+    fn set_supplier(&mut self, supplier: Option<String>) {
+        self.supplier = supplier;
+    }
+
+    #[getter(type)]
+    fn py_type(&self) -> Option<String> {
+        self.material_type.clone()
+    }
+
+    #[setter(type)]
+    fn set_py_type(&mut self, material_type: Option<String>) {
+        self.material_type = material_type;
+    }
+
+    #[getter]
+    fn id(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    #[setter]
+    fn set_id(&mut self, id: Option<String>) {
+        self.id = id;
+    }
+
+    #[getter]
+    fn scope(&self) -> Option<String> {
+        self.scope.clone()
+    }
+
+    #[setter]
+    fn set_scope(&mut self, scope: Option<String>) {
+        self.scope = scope;
+    }
+
+    #[getter]
+    fn dependency_kinds(&self) -> Vec<String> {
+        self.dependency_kinds.clone()
+    }
+
+    #[getter]
+    fn file_path(&self) -> Option<String> {
+        self.file_path.clone()
+    }
+
+    #[getter]
+    fn license_file(&self) -> Option<String> {
+        self.license_file.clone()
+    }
+
+    #[getter]
+    fn parent(&self) -> Option<String> {
+        self.parent.clone()
+    }
+
+    #[getter]
+    fn children(&self) -> Vec<String> {
+        self.children.clone()
+    }
+
+    #[getter]
+    fn is_project(&self) -> bool {
+        self.is_project
+    }
+
+    #[getter]
+    fn cpe(&self) -> Option<String> {
+        self.cpe.clone()
+    }
+
+    #[setter]
+    fn set_cpe(&mut self, cpe: Option<String>) {
+        self.cpe = cpe;
+    }
+
+    #[getter]
+    fn author(&self) -> Option<String> {
+        self.author.clone()
+    }
+
+    #[setter]
+    fn set_author(&mut self, author: Option<String>) {
+        self.author = author;
+    }
+
+    #[getter]
+    fn publisher(&self) -> Option<String> {
+        self.publisher.clone()
+    }
+
+    #[setter]
+    fn set_publisher(&mut self, publisher: Option<String>) {
+        self.publisher = publisher;
+    }
+
+    #[getter]
+    fn copyright(&self) -> Option<String> {
+        self.copyright.clone()
+    }
+
+    #[setter]
+    fn set_copyright(&mut self, copyright: Option<String>) {
+        self.copyright = copyright;
+    }
+
+    #[getter]
+    fn hashes(&self) -> HashMap<String, String> {
+        self.hashes.clone()
+    }
+
+    #[getter]
+    fn external_references(&self) -> Vec<ExternalReference> {
+        self.external_references.clone()
+    }
+
+    #[getter]
+    fn declared_licenses(&self) -> Vec<String> {
+        self.declared_licenses.clone()
+    }
+
+    #[setter]
+    fn set_declared_licenses(&mut self, licenses: Vec<String>) {
+        self.declared_licenses = licenses;
+    }
+
+    #[getter]
+    fn concluded_licenses(&self) -> Vec<String> {
+        self.concluded_licenses.clone()
+    }
+
+    #[setter]
+    fn set_concluded_licenses(&mut self, licenses: Vec<String>) {
+        self.concluded_licenses = licenses;
+    }
+
+    #[getter]
+    fn detected_licenses(&self) -> Vec<String> {
+        self.detected_licenses.clone()
+    }
+
+    #[setter]
+    fn set_detected_licenses(&mut self, licenses: Vec<String>) {
+        self.detected_licenses = licenses;
+    }
+
+    #[getter]
+    fn licenses(&self) -> Vec<String> {
+        self.licenses.clone()
+    }
+
+    #[setter]
+    fn set_licenses(&mut self, licenses: Vec<String>) {
+        self.set_license_strings(licenses);
+    }
+
+    #[getter]
+    fn license_expression(&self) -> Option<LicenseExpression> {
+        self.license_expression.clone()
+    }
+
+    #[getter]
+    fn license_categories(&self) -> HashMap<String, String> {
+        self.license_categories.clone()
+    }
+
+    #[getter]
+    fn annotations(&self) -> HashMap<String, String> {
+        self.annotations.clone()
+    }
+
+    #[setter]
+    fn set_annotations(&mut self, annotations: HashMap<String, String>) {
+        self.annotations = annotations;
+    }
+
     fn update_annotation(&mut self, key: String, value: String) {
         self.annotations.insert(key, value);
     }
-}
 
+    #[getter]
+    fn is_direct(&self) -> Option<bool> {
+        self.is_direct
+    }
+
+    #[getter]
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    #[getter]
+    fn dependents(&self) -> Vec<String> {
+        self.dependents.clone()
+    }
+
+    #[pyo3(name = "paths_from_root")]
+    fn py_paths_from_root(&self) -> Vec<Vec<String>> {
+        self.paths_from_root.clone()
+    }
+
+    fn curate_reason(&mut self, reason: String) {
+        self.curation_reasons.push(reason);
+    }
+
+    /// Accepts a `LicenseExpression`, an expression string or `None`.
+    #[setter]
+    fn set_license_expression(&mut self, expression: Option<&PyAny>) -> PyResult<()> {
+        let expression = match expression {
+            None => None,
+            Some(value) => Some(LicenseExpression::from_py(value)?),
+        };
+
+        match expression {
+            Some(expression) => self.set_expression(expression),
+            None => {
+                self.licenses = Vec::new();
+                self.license_expression = None;
+            }
+        }
+
+        Ok(())
+    }
+}
 
 #[derive(Debug, Deserialize)]
 struct SourceTsv {
@@ -41,16 +463,102 @@ struct SourceTsv {
     version: String,
     licenses: String,
     annotations: String,
+    #[serde(default)]
+    purl: Option<String>,
+    #[serde(default)]
+    group: Option<String>,
+    #[serde(default)]
+    supplier: Option<String>,
+    #[serde(rename = "type", default)]
+    material_type: Option<String>,
+    #[serde(default)]
+    id: Option<String>,
+}
+
+/// Options of loaders, given by `EvaluateArgs`.
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// Sources of SPDX package licenses in order of precedence.
+    pub spdx_license_sources: Vec<SpdxLicenseSource>,
+
+    /// Load files and snippets of SPDX as materials in addition to packages.
+    pub spdx_files: bool,
+
+    /// Load local BOMs referred by CycloneDX `externalReferences` of type `bom`.
+    pub follow_bom_references: bool,
+
+    /// Path of the source. Relative paths of BOM references are resolved from its directory.
+    pub source_path: Option<PathBuf>,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            spdx_license_sources: vec![
+                SpdxLicenseSource::Concluded,
+                SpdxLicenseSource::Declared,
+                SpdxLicenseSource::Files,
+            ],
+            spdx_files: false,
+            follow_bom_references: false,
+            source_path: None,
+        }
+    }
 }
 
-pub fn load_materials(source: &File, source_type: &SourceType) -> Result<Vec<Material>, Error> {
-    match source_type {
+pub fn load_materials(
+    source: &File,
+    source_type: &SourceType,
+    options: &LoadOptions,
+) -> Result<Vec<Material>, Error> {
+    let mut materials = match source_type {
         SourceType::Tsv => load_materials_tsv(source),
-        SourceType::SpdxTag => load_materials_spdx_tag(source),
-        SourceType::SpdxJson => load_materials_spdx_json(source),
-        SourceType::SpdxYaml => load_materials_spdx_json(source), // yaml parser equals json parser
-        SourceType::CycloneDxJson => load_materials_cyclonedx_json(source),
-        SourceType::CycloneDxXml => load_materials_cyclonedx_xml(source),
+        SourceType::SpdxTag => load_materials_spdx_tag(source, options),
+        SourceType::SpdxJson => load_materials_spdx_json(source, options),
+        SourceType::SpdxYaml => load_materials_spdx_yaml(source, options),
+        SourceType::SpdxRdf => load_materials_spdx_rdf(source, options),
+        SourceType::SpdxXlsx => load_materials_spdx_xlsx(source, options),
+        SourceType::Spdx3Json => load_materials_spdx3_json(source, options),
+        SourceType::CycloneDxJson => load_materials_cyclonedx_json(source, options),
+        SourceType::CycloneDxXml => load_materials_cyclonedx_xml(source, options),
+        SourceType::CycloneDxProtobuf => load_materials_cyclonedx_protobuf(source, options),
+        SourceType::CargoMetadata => load_materials_cargo_metadata(source),
+        SourceType::Npm => load_materials_npm(source, options),
+    }?;
+
+    link_children(&mut materials);
+
+    Ok(materials)
+}
+
+/// Load materials from stdin, e.g. `cargo metadata --format-version 1 | hatto evaluate -`. Other source types than
+/// `cargo-metadata` need a file.
+pub fn load_materials_stdin(source_type: &SourceType) -> Result<Vec<Material>, Error> {
+    let mut materials = match source_type {
+        SourceType::CargoMetadata => load_materials_cargo_metadata(std::io::stdin().lock()),
+        _ => Err(Error::Failure(
+            "stdin is supported only by cargo-metadata source type".to_string(),
+        )),
+    }?;
+
+    link_children(&mut materials);
+
+    Ok(materials)
+}
+
+/// Set `children` from `parent` of materials. Both are ids, since names are not unique.
+fn link_children(materials: &mut [Material]) {
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    for material in materials.iter() {
+        if let (Some(parent), Some(id)) = (&material.parent, &material.id) {
+            children.entry(parent.clone()).or_default().push(id.clone());
+        }
+    }
+
+    for material in materials.iter_mut() {
+        if let Some(ids) = material.id.as_ref().and_then(|id| children.remove(id)) {
+            material.children = ids;
+        }
     }
 }
 
@@ -73,131 +581,1252 @@ fn load_materials_tsv(source: &File) -> Result<Vec<Material>, Error> {
                 (kv.next().unwrap_or_else(|| "".to_string()), kv.next().unwrap_or_else(|| "".to_string())) 
             })
             .collect::<HashMap<String, String>>();
-        let material = Material {
+        let mut material = Material {
             name: tsv.name,
             version: Some(tsv.version),
-            licenses: tsv.licenses.split(',').map(|s| s.to_string()).collect(),
+            purl: tsv.purl,
+            group: tsv.group,
+            supplier: tsv.supplier,
+            material_type: tsv.material_type,
+            id: tsv.id,
             annotations,
+            ..Default::default()
         };
+        material.set_license_strings(tsv.licenses.split(',').map(|s| s.to_string()).collect());
         materials.push(material);
     }
 
     Ok(materials)
 }
 
-fn load_materials_spdx_tag(source: &File) -> Result<Vec<Material>, Error> {
+fn load_materials_spdx_tag(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
     let mut content = String::new();
     let mut reader = BufReader::new(source);
     reader.read_to_string(&mut content)?;
     let spdx = spdx_from_tag_value(&content)?;
 
-    extract_spdx(&spdx)
+    extract_spdx(&spdx, options)
 }
 
-fn load_materials_spdx_json(source: &File) -> Result<Vec<Material>, Error> {
+fn load_materials_spdx_json(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
     let spdx: SPDX = serde_json::from_reader(source)?;
 
-    extract_spdx(&spdx)
+    extract_spdx(&spdx, options)
 }
 
-fn extract_spdx(spdx: &SPDX) -> Result<Vec<Material>, Error> {
+fn load_materials_spdx_yaml(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
+    let spdx = load_yaml_file(source)?;
+
+    extract_spdx(&spdx, options)
+}
+
+fn load_materials_spdx_rdf(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
+    let spdx = load_rdf_file(source)?;
+
+    extract_spdx(&spdx, options)
+}
+
+fn load_materials_spdx_xlsx(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
+    let spdx = load_xlsx_file(source)?;
+
+    extract_spdx(&spdx, options)
+}
+
+fn load_materials_spdx3_json(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
+    let spdx = load_spdx3_json_file(source)?;
+
+    extract_spdx(&spdx, options)
+}
+
+fn extract_spdx(spdx: &SPDX, options: &LoadOptions) -> Result<Vec<Material>, Error> {
     let mut materials: Vec<Material> = Vec::new();
     for pi in &spdx.package_information {
-        let concluded_licenses = pi
-            .concluded_license
-            .licenses()
-            .iter()
-            .map(|&license| license.identifier.clone())
-            .collect::<Vec<_>>();
-
-        let licenses = if concluded_licenses.len() == 1 && concluded_licenses[0] == "NONE" {
-            pi.all_licenses_information_from_files.to_vec()
-        } else if concluded_licenses.is_empty() {
-            concluded_licenses 
-        } else {
-            pi.all_licenses_information_from_files.to_vec()
-        };
+        let declared_licenses = spdx_license_strings(pi.declared_license.as_ref().map(|e| e.to_string()));
+        let concluded_licenses = spdx_license_strings(pi.concluded_license.as_ref().map(|e| e.to_string()));
+        let detected_licenses = spdx_license_strings(pi.all_licenses_information_from_files.iter().cloned());
+
+        let licenses = select_spdx_licenses(options, &concluded_licenses, &declared_licenses, &detected_licenses);
 
-        let material = Material {
+        let mut material = Material {
             name: pi.package_name.clone(),
             version: pi.package_version.clone(),
-            licenses,
-            annotations: HashMap::new(),
+            purl: pi
+                .external_reference
+                .iter()
+                .find(|reference| reference.reference_type == "purl")
+                .map(|reference| reference.reference_locator.clone()),
+            supplier: pi.package_supplier.as_deref().and_then(spdx_actor_name),
+            material_type: pi
+                .primary_package_purpose
+                .and_then(|purpose| serde_json::to_value(purpose).ok())
+                .and_then(|purpose| purpose.as_str().map(|p| p.to_lowercase())),
+            id: Some(pi.package_spdx_identifier.clone()),
+            declared_licenses,
+            concluded_licenses,
+            detected_licenses,
+            ..Default::default()
         };
+        material.set_license_strings(licenses);
         materials.push(material);
     }
 
+    if options.spdx_files {
+        extract_spdx_files(spdx, options, &mut materials);
+    }
+
+    extract_spdx_graph(spdx).apply(&mut materials);
+
     Ok(materials)
 }
 
-fn load_materials_cyclonedx_json(source: &File) -> Result<Vec<Material>, Error> {
+/// Files and snippets as materials, so that a file licensed differently from its package can be found.
+fn extract_spdx_files(spdx: &SPDX, options: &LoadOptions, materials: &mut Vec<Material>) {
+    let package_id = |id: &str| {
+        spdx.package_information
+            .iter()
+            .find(|pi| pi.package_spdx_identifier == id)
+            .map(|pi| pi.package_spdx_identifier.clone())
+    };
+    let package_of = |file_id: &str| {
+        spdx.package_information
+            .iter()
+            .find(|pi| pi.files.iter().any(|file| file == file_id))
+            .map(|pi| pi.package_spdx_identifier.clone())
+            .or_else(|| {
+                spdx.relationships.iter().find_map(|relationship| {
+                    match relationship.relationship_type {
+                        RelationshipType::Contains if relationship.related_spdx_element == file_id => {
+                            package_id(&relationship.spdx_element_id)
+                        }
+                        RelationshipType::ContainedBy if relationship.spdx_element_id == file_id => {
+                            package_id(&relationship.related_spdx_element)
+                        }
+                        _ => None,
+                    }
+                })
+            })
+    };
+
+    for fi in &spdx.file_information {
+        let concluded_licenses = spdx_license_strings(fi.concluded_license.as_ref().map(|e| e.to_string()));
+        let detected_licenses = spdx_license_strings(fi.license_information_in_file.iter().map(|e| e.to_string()));
+        let licenses = select_spdx_licenses(options, &concluded_licenses, &[], &detected_licenses);
+
+        let mut material = Material {
+            name: fi.file_name.clone(),
+            material_type: Some("file".to_string()),
+            id: Some(fi.file_spdx_identifier.clone()),
+            file_path: Some(fi.file_name.clone()),
+            parent: package_of(&fi.file_spdx_identifier),
+            copyright: fi.copyright_text.as_deref().and_then(spdx_text),
+            hashes: fi
+                .file_checksum
+                .iter()
+                .filter_map(|checksum| {
+                    let algorithm = serde_json::to_value(checksum.algorithm).ok()?;
+                    Some((algorithm.as_str()?.to_string(), checksum.value.clone()))
+                })
+                .collect(),
+            concluded_licenses,
+            detected_licenses,
+            ..Default::default()
+        };
+        material.set_license_strings(licenses);
+        materials.push(material);
+    }
+
+    for snippet in &spdx.snippet_information {
+        let file_path = spdx
+            .file_information
+            .iter()
+            .find(|fi| fi.file_spdx_identifier == snippet.snippet_from_file_spdx_identifier)
+            .map(|fi| fi.file_name.clone());
+        let concluded_licenses =
+            spdx_license_strings(snippet.snippet_concluded_license.as_ref().map(|e| e.to_string()));
+        let detected_licenses = spdx_license_strings(snippet.license_information_in_snippet.iter().cloned());
+        let licenses = select_spdx_licenses(options, &concluded_licenses, &[], &detected_licenses);
+
+        let mut material = Material {
+            name: snippet
+                .snippet_name
+                .clone()
+                .unwrap_or_else(|| snippet.snippet_spdx_identifier.clone()),
+            material_type: Some("snippet".to_string()),
+            id: Some(snippet.snippet_spdx_identifier.clone()),
+            file_path,
+            parent: Some(snippet.snippet_from_file_spdx_identifier.clone()),
+            copyright: snippet.snippet_copyright_text.as_deref().and_then(spdx_text),
+            concluded_licenses,
+            detected_licenses,
+            ..Default::default()
+        };
+        material.set_license_strings(licenses);
+        materials.push(material);
+    }
+}
+
+/// The first source in `--spdx-license-source` order that asserts licenses. `NONE` asserts no license, so it is used.
+fn select_spdx_licenses(
+    options: &LoadOptions,
+    concluded: &[String],
+    declared: &[String],
+    files: &[String],
+) -> Vec<String> {
+    options
+        .spdx_license_sources
+        .iter()
+        .map(|source| match source {
+            SpdxLicenseSource::Concluded => concluded,
+            SpdxLicenseSource::Declared => declared,
+            SpdxLicenseSource::Files => files,
+        })
+        .find(|licenses| !licenses.is_empty())
+        .map(|licenses| licenses.to_vec())
+        .unwrap_or_default()
+}
+
+/// Text field of SPDX. `NOASSERTION` and `NONE` are `None`.
+fn spdx_text(text: &str) -> Option<String> {
+    match text.trim() {
+        "" | "NOASSERTION" | "NONE" => None,
+        text => Some(text.to_string()),
+    }
+}
+
+/// `NOASSERTION` asserts nothing, so it is dropped. `NONE` is kept, because it asserts that there is no license.
+/// `NONE` in files is dropped if licenses are also found in other files.
+fn spdx_license_strings<I: IntoIterator<Item = String>>(licenses: I) -> Vec<String> {
+    let licenses = licenses
+        .into_iter()
+        .map(|license| license.trim().to_string())
+        .filter(|license| !matches!(license.as_str(), "" | "NOASSERTION"))
+        .collect::<Vec<_>>();
+
+    match licenses.iter().all(|license| license == "NONE") {
+        true => licenses.into_iter().take(1).collect(),
+        false => licenses.into_iter().filter(|license| license != "NONE").collect(),
+    }
+}
+
+fn extract_spdx_graph(spdx: &SPDX) -> DependencyGraph {
+    let mut graph = DependencyGraph::default();
+    let document_id = &spdx.document_creation_information.spdx_identifier;
+
+    for described in &spdx.document_creation_information.document_describes {
+        graph.add_root(described);
+    }
+
+    for relationship in &spdx.relationships {
+        let element = &relationship.spdx_element_id;
+        let related = &relationship.related_spdx_element;
+
+        match relationship.relationship_type {
+            RelationshipType::Describes if element == document_id => graph.add_root(related),
+            RelationshipType::DescribedBy if related == document_id => graph.add_root(element),
+            // contained elements (e.g. files of package) are shipped with the container, so that they are treated as
+            // its dependencies to find the paths from roots
+            RelationshipType::DependsOn | RelationshipType::Contains => graph.add_dependency(element, related),
+            RelationshipType::DependencyOf
+            | RelationshipType::BuildDependencyOf
+            | RelationshipType::DevDependencyOf
+            | RelationshipType::OptionalDependencyOf
+            | RelationshipType::ProvidedDependencyOf
+            | RelationshipType::TestDependencyOf
+            | RelationshipType::RuntimeDependencyOf
+            | RelationshipType::ContainedBy => graph.add_dependency(related, element),
+            _ => (),
+        }
+    }
+
+    graph
+}
+
+/// `Organization: Foo (foo@example.com)` to `Foo`. `NOASSERTION` is `None`.
+fn spdx_actor_name(actor: &str) -> Option<String> {
+    let actor = actor.trim();
+    if actor.is_empty() || actor == "NOASSERTION" {
+        return None;
+    }
+
+    let name = actor
+        .strip_prefix("Organization:")
+        .or_else(|| actor.strip_prefix("Person:"))
+        .or_else(|| actor.strip_prefix("Tool:"))
+        .unwrap_or(actor);
+    let name = match name.find('(') {
+        Some(i) => &name[..i],
+        None => name,
+    };
+
+    Some(name.trim().to_string())
+}
+
+fn load_materials_cyclonedx_json(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
     let bom = load_json_file(source)?;
 
-    extract_cyclonedx(&bom)
+    extract_cyclonedx(&bom, options)
 }
 
-fn load_materials_cyclonedx_xml(source: &File) -> Result<Vec<Material>, Error> {
+fn load_materials_cyclonedx_xml(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
     let bom = load_xml_file(source)?;
 
-    extract_cyclonedx(&bom)
+    extract_cyclonedx(&bom, options)
 }
 
-fn extract_cyclonedx(bom: &Bom) -> Result<Vec<Material>, Error> {
+fn load_materials_cyclonedx_protobuf(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
+    let bom = load_protobuf_file(source)?;
+
+    extract_cyclonedx(&bom, options)
+}
+
+fn extract_cyclonedx(bom: &Bom, options: &LoadOptions) -> Result<Vec<Material>, Error> {
     let mut materials: Vec<Material> = Vec::new();
+    let mut graph = DependencyGraph::default();
+
+    if let Some(component) = bom.metadata.as_ref().and_then(|metadata| metadata.component.as_ref()) {
+        let mut project = cyclonedx_material(component, None)?;
+        project.is_project = true;
+        materials.push(project);
+
+        if let Some(bom_ref) = &component.bom_ref {
+            graph.add_root(bom_ref);
+        }
+        if let Some(components) = &component.components {
+            extract_cyclonedx_components(components, component.bom_ref.as_deref(), &mut materials)?;
+        }
+    }
 
     if let Some(components) = &bom.components {
-        extract_cyclonedx_components(components, &mut materials)?
+        extract_cyclonedx_components(components, None, &mut materials)?
+    }
+
+    extract_cyclonedx_dependencies(bom, None, &mut graph);
+
+    if options.follow_bom_references {
+        let mut visited: HashSet<PathBuf> = HashSet::new();
+        let dir = match &options.source_path {
+            Some(path) => {
+                visited.insert(path.canonicalize().unwrap_or_else(|_| path.clone()));
+                path.parent().map(|dir| dir.to_path_buf()).unwrap_or_default()
+            }
+            None => PathBuf::new(),
+        };
+        extract_cyclonedx_bom_references(bom, &dir, &mut visited, &mut materials, &mut graph)?;
     }
 
+    graph.apply(&mut materials);
+
     Ok(materials)
 }
 
-fn extract_cyclonedx_components(components: &Components, materials: &mut Vec<Material>) -> Result<(), Error> {
-    for component in &components.0 {
-        let licenses = match &component.licenses {
-            Some(l) => extract_cyclonedx_licenses(l)?,
-            None => Vec::new(),
-        };
+/// Add dependencies of BOM to graph. `alias` is the id used instead of the root component of BOM, because the root of
+/// a referred BOM is the component referring to it.
+fn extract_cyclonedx_dependencies(bom: &Bom, alias: Option<&str>, graph: &mut DependencyGraph) {
+    let root = bom
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.component.as_ref())
+        .and_then(|component| component.bom_ref.as_deref());
+    let id = |id: &str| match (root, alias) {
+        (Some(root), Some(alias)) if root == id => alias.to_string(),
+        _ => id.to_string(),
+    };
 
-        let material = Material {
-            name: component.name.clone(),
-            version: component.version.clone(),
-            licenses,
-            annotations: HashMap::new(),
-        };
+    for dependency in bom.dependencies.iter().flatten() {
+        for depends_on in dependency.depends_on.iter().flatten() {
+            graph.add_dependency(&id(&dependency.dependency_ref), &id(depends_on));
+        }
+    }
+}
 
-        materials.push(material);
+/// Load local BOMs referred by components, and extract their components as children of the referring components.
+/// URLs other than local files (e.g. `https:` or BOM-Link `urn:cdx:`) are ignored.
+fn extract_cyclonedx_bom_references(
+    bom: &Bom,
+    dir: &Path,
+    visited: &mut HashSet<PathBuf>,
+    materials: &mut Vec<Material>,
+    graph: &mut DependencyGraph,
+) -> Result<(), Error> {
+    let mut components: Vec<&Component> = Vec::new();
+    if let Some(component) = bom.metadata.as_ref().and_then(|metadata| metadata.component.as_ref()) {
+        collect_cyclonedx_components(std::slice::from_ref(component), &mut components);
+    }
+    if let Some(bom_components) = &bom.components {
+        collect_cyclonedx_components(&bom_components.0, &mut components);
+    }
+
+    for component in components {
+        for reference in component.external_references.iter().flatten() {
+            if reference.reference_type != "bom" {
+                continue;
+            }
+            let path = match local_bom_path(&reference.url, dir) {
+                Some(path) => path,
+                None => continue,
+            };
+            // the same BOM can be referred by many components, and BOMs can refer each other
+            if !visited.insert(path.canonicalize().unwrap_or_else(|_| path.clone())) {
+                continue;
+            }
+
+            let source = File::open(&path)?;
+            let referred_bom = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("xml") => load_xml_file(&source)?,
+                Some("bin") => load_protobuf_file(&source)?,
+                _ => load_json_file(&source)?,
+            };
+
+            if let Some(components) = &referred_bom.components {
+                extract_cyclonedx_components(components, component.bom_ref.as_deref(), materials)?;
+            }
+            extract_cyclonedx_dependencies(&referred_bom, component.bom_ref.as_deref(), graph);
+
+            let referred_dir = path.parent().unwrap_or(dir).to_path_buf();
+            extract_cyclonedx_bom_references(&referred_bom, &referred_dir, visited, materials, graph)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn collect_cyclonedx_components<'a>(components: &'a [Component], collected: &mut Vec<&'a Component>) {
+    for component in components {
+        collected.push(component);
+        if let Some(children) = &component.components {
+            collect_cyclonedx_components(&children.0, collected);
+        }
+    }
+}
+
+/// Local path of BOM reference, e.g. `sub.cdx.json`, `./sub/bom.xml` or `file:///path/to/bom.json`.
+fn local_bom_path(url: &str, dir: &Path) -> Option<PathBuf> {
+    let path = match url.strip_prefix("file://") {
+        Some(path) => path,
+        None if url.contains("://") || url.starts_with("urn:") => return None,
+        None => url,
+    };
+
+    Some(dir.join(path))
+}
+
+fn extract_cyclonedx_components(
+    components: &Components,
+    parent: Option<&str>,
+    materials: &mut Vec<Material>,
+) -> Result<(), Error> {
+    for component in &components.0 {
+        materials.push(cyclonedx_material(component, parent)?);
 
         if let Some(components) = &component.components {
-            extract_cyclonedx_components(components, materials)?;
+            extract_cyclonedx_components(components, component.bom_ref.as_deref(), materials)?;
         }
     }
 
     Ok(())
 }
 
-fn extract_cyclonedx_licenses(licenses: &Licenses) -> Result<Vec<String>, Error> {
+fn cyclonedx_material(component: &Component, parent: Option<&str>) -> Result<Material, Error> {
+    let mut material = Material {
+        name: component.name.clone(),
+        version: component.version.clone(),
+        purl: component.purl.clone(),
+        group: component.group.clone(),
+        supplier: component
+            .supplier
+            .as_ref()
+            .and_then(|supplier| supplier.name.clone())
+            .or_else(|| component.publisher.clone()),
+        material_type: match component.component_type.is_empty() {
+            true => None,
+            false => Some(component.component_type.clone()),
+        },
+        id: component.bom_ref.clone(),
+        scope: component.scope.as_ref().map(|scope| scope.trim().to_lowercase()),
+        parent: parent.map(|parent| parent.to_string()),
+        cpe: component.cpe.clone(),
+        author: component.author.clone().or_else(|| {
+            let names = component
+                .authors
+                .iter()
+                .flatten()
+                .filter_map(|author| author.name.clone())
+                .collect::<Vec<_>>();
+            match names.is_empty() {
+                true => None,
+                false => Some(names.join(", ")),
+            }
+        }),
+        publisher: component.publisher.clone(),
+        copyright: component.copyright.clone(),
+        hashes: component
+            .hashes
+            .iter()
+            .flatten()
+            .map(|hash| (hash.alg.clone(), hash.content.clone()))
+            .collect(),
+        external_references: component
+            .external_references
+            .iter()
+            .flatten()
+            .map(|reference| ExternalReference {
+                reference_type: reference.reference_type.clone(),
+                url: reference.url.clone(),
+                comment: reference.comment.clone(),
+            })
+            .collect(),
+        // properties are annotations, so that policies can use them in the same way
+        annotations: component
+            .properties
+            .iter()
+            .flatten()
+            .map(|property| (property.name.clone(), property.value.clone().unwrap_or_default()))
+            .collect(),
+        ..Default::default()
+    };
+
+    // licenses found as evidence (e.g. by scanners) are used only if no license is given
+    let licenses = component.licenses.as_ref().or_else(|| {
+        component
+            .evidence
+            .as_ref()
+            .and_then(|evidence| evidence.licenses.as_ref())
+    });
+    if let Some(licenses) = licenses {
+        extract_cyclonedx_licenses(licenses, &mut material)?;
+    }
+
+    // licenses without acknowledgement (before CycloneDX 1.6) are declared by the authors
+    for license_choice in component.licenses.iter().flat_map(|licenses| &licenses.0) {
+        if let Some(text) = license_choice.text() {
+            match license_choice.acknowledgement() {
+                Some("concluded") => material.concluded_licenses.push(text.to_string()),
+                _ => material.declared_licenses.push(text.to_string()),
+            }
+        }
+    }
+    material.detected_licenses = component
+        .evidence
+        .iter()
+        .flat_map(|evidence| evidence.licenses.iter().flat_map(|licenses| &licenses.0))
+        .filter_map(|license_choice| license_choice.text().map(|text| text.to_string()))
+        .collect();
+
+    Ok(material)
+}
+
+fn extract_cyclonedx_licenses(licenses: &Licenses, material: &mut Material) -> Result<(), Error> {
     let mut lis: Vec<String> = Vec::new();
+    let mut expressions: Vec<LicenseExpression> = Vec::new();
+    let mut is_expression = true;
 
     for license_choice in &licenses.0 {
         match license_choice {
             LicenseChoice::License(license) => {
-                if license.id != None {
-                    if let Some(id) = &license.id {
-                        lis.push(id.clone());
-                    }
-                } else if license.name != None {
-                    if let Some(name) = &license.name {
-                        lis.push(name.clone());
+                if let Some(id) = &license.id {
+                    lis.push(id.clone());
+                    match LicenseExpression::parse(id) {
+                        Ok(expression) => expressions.push(expression),
+                        Err(_) => is_expression = false,
                     }
+                } else if let Some(name) = &license.name {
+                    lis.push(name.clone());
+                    is_expression = false;
                 }
-            },
+            }
             LicenseChoice::Expression(expression) => {
-                let spdx_expression = SpdxExpression::parse(&expression)?;
-                lis.append(&mut spdx_expression.identifiers().iter().map(|s| s.to_string()).collect::<Vec<String>>());
+                let expression = LicenseExpression::parse(&expression.expression)?;
+                lis.append(&mut expression.license_ids());
+                expressions.push(expression);
+            }
+        }
+    }
+
+    match LicenseExpression::conjunction(expressions) {
+        Some(expression) if is_expression => material.set_expression(expression),
+        _ => material.licenses = lis,
+    }
+
+    Ok(())
+}
+
+fn load_materials_cargo_metadata<R: Read>(source: R) -> Result<Vec<Material>, Error> {
+    let metadata = load_metadata(source)?;
+
+    extract_cargo_metadata(&metadata)
+}
+
+/// Packages of `cargo metadata` become materials, and workspace members are the roots of dependency graph. Packages
+/// reached only by `dev` or `build` dependencies are `excluded` scope, because they are not shipped.
+fn extract_cargo_metadata(metadata: &Metadata) -> Result<Vec<Material>, Error> {
+    let mut graph = DependencyGraph::default();
+    for member in &metadata.workspace_members {
+        graph.add_root(member);
+    }
+    for node in metadata.resolve.iter().flat_map(|resolve| &resolve.nodes) {
+        for dep in &node.deps {
+            graph.add_dependency(&node.id, &dep.pkg);
+        }
+    }
+
+    let mut dependency_kinds = cargo_dependency_kinds(metadata);
+
+    let mut materials: Vec<Material> = Vec::new();
+    for package in &metadata.packages {
+        let mut material = Material {
+            name: package.name.clone(),
+            version: Some(package.version.clone()),
+            purl: package.purl(),
+            material_type: match package.is_application() {
+                true => Some("application".to_string()),
+                false => Some("library".to_string()),
+            },
+            id: Some(package.id.clone()),
+            // relative to the manifest, so that policies can read the file
+            license_file: package.license_file.as_ref().map(|license_file| {
+                let dir = Path::new(&package.manifest_path).parent().unwrap_or_else(|| Path::new(""));
+                dir.join(license_file).to_string_lossy().to_string()
+            }),
+            author: match package.authors.is_empty() {
+                true => None,
+                false => Some(package.authors.join(", ")),
+            },
+            external_references: [
+                ("vcs", &package.repository),
+                ("website", &package.homepage),
+                ("documentation", &package.documentation),
+            ]
+            .into_iter()
+            .filter_map(|(reference_type, url)| {
+                url.as_ref().map(|url| ExternalReference {
+                    reference_type: reference_type.to_string(),
+                    url: url.clone(),
+                    comment: None,
+                })
+            })
+            .collect(),
+            ..Default::default()
+        };
+
+        // workspace members are the project being evaluated, as the root component of CycloneDX
+        if metadata.workspace_members.contains(&package.id) {
+            material.is_project = true;
+        } else if let Some(kinds) = dependency_kinds.remove(&package.id) {
+            material.scope = match kinds.iter().any(|kind| kind == "normal") {
+                true => Some("required".to_string()),
+                false => Some("excluded".to_string()),
+            };
+            material.dependency_kinds = kinds;
+        }
+
+        // `/` is the deprecated separator of `OR` in Cargo, e.g. `MIT/Apache-2.0`
+        if let Some(license) = &package.license {
+            let license = license.split('/').map(|id| id.trim()).collect::<Vec<_>>().join(" OR ");
+            material.declared_licenses = vec![license];
+            material.set_license_strings(material.declared_licenses.clone());
+        }
+
+        materials.push(material);
+    }
+
+    graph.apply(&mut materials);
+
+    Ok(materials)
+}
+
+/// Kinds of dependency by which packages are reached from workspace members, in the order of `normal`, `build` and
+/// `dev`. Packages below a `dev` or `build` dependency are reached by the same kind.
+fn cargo_dependency_kinds(metadata: &Metadata) -> HashMap<String, Vec<String>> {
+    const KINDS: [&str; 3] = ["normal", "build", "dev"];
+    let rank = |kind: &str| KINDS.iter().position(|k| *k == kind).unwrap_or(0);
+
+    let nodes = metadata
+        .resolve
+        .iter()
+        .flat_map(|resolve| &resolve.nodes)
+        .map(|node| (node.id.as_str(), node))
+        .collect::<HashMap<_, _>>();
+
+    let mut reached: HashSet<(&str, usize)> = HashSet::new();
+    let mut queue: VecDeque<(&str, usize)> = metadata
+        .workspace_members
+        .iter()
+        .map(|member| (member.as_str(), 0))
+        .collect();
+    while let Some((id, id_rank)) = queue.pop_front() {
+        for dep in nodes.get(id).into_iter().flat_map(|node| &node.deps) {
+            for dep_kind in &dep.dep_kinds {
+                let next = (dep.pkg.as_str(), id_rank.max(rank(dep_kind.name())));
+                if reached.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    let mut kinds: HashMap<String, Vec<usize>> = HashMap::new();
+    for (id, id_rank) in reached {
+        kinds.entry(id.to_string()).or_default().push(id_rank);
+    }
+    kinds
+        .into_iter()
+        .map(|(id, mut ranks)| {
+            ranks.sort();
+            (id, ranks.into_iter().map(|r| KINDS[r].to_string()).collect())
+        })
+        .collect()
+}
+
+fn load_materials_npm(source: &File, options: &LoadOptions) -> Result<Vec<Material>, Error> {
+    let lock = load_package_lock(source)?;
+
+    // paths of the lockfile are relative to the project, where node_modules is installed
+    let project_dir = options
+        .source_path
+        .as_ref()
+        .and_then(|path| path.parent())
+        .map(|dir| dir.to_path_buf())
+        .unwrap_or_default();
+
+    extract_npm(&lock, &project_dir)
+}
+
+/// Packages of `package-lock.json` become materials, and the project (`""`) and workspaces are the roots of dependency
+/// graph. `package.json` installed in `node_modules` gives the licenses missing in the lockfile.
+fn extract_npm(lock: &PackageLock, project_dir: &Path) -> Result<Vec<Material>, Error> {
+    let mut materials: Vec<Material> = Vec::new();
+    let mut graph = DependencyGraph::default();
+
+    for (path, package) in &lock.packages {
+        // links are duplicates of their targets, e.g. workspaces
+        if package.link {
+            continue;
+        }
+        let name = match lock.package_name(path) {
+            Some(name) => name,
+            None => continue,
+        };
+        let is_project = path.is_empty();
+        let is_local = PackageLock::is_local(path);
+        if is_local {
+            graph.add_root(path);
+        }
+
+        let package_json = load_installed_package_json(&project_dir.join(path))?.unwrap_or_default();
+
+        let mut external_references = Vec::new();
+        for (reference_type, url) in [
+            ("distribution", package.resolved.clone().filter(|_| !is_local)),
+            ("vcs", package_json.repository()),
+            ("website", package_json.homepage.clone()),
+        ] {
+            if let Some(url) = url {
+                external_references.push(ExternalReference {
+                    reference_type: reference_type.to_string(),
+                    url,
+                    comment: None,
+                });
             }
         }
+
+        let mut material = Material {
+            purl: match is_local {
+                true => None,
+                false => package.purl(&name),
+            },
+            name,
+            version: package.version.clone().or_else(|| match is_project {
+                true => lock.version.clone(),
+                false => None,
+            }),
+            material_type: match is_project {
+                true => Some("application".to_string()),
+                false => Some("library".to_string()),
+            },
+            id: Some(path.clone()),
+            scope: match is_project {
+                true => None,
+                false => Some(package.scope().to_string()),
+            },
+            is_project,
+            author: package_json.author(),
+            external_references,
+            ..Default::default()
+        };
+
+        // old lockfiles and packages lack `license` in the lockfile
+        if let Some(license) = package.license().or_else(|| package_json.license()) {
+            material.declared_licenses = vec![license];
+            material.set_license_strings(material.declared_licenses.clone());
+        }
+
+        for dependency in package.dependency_names() {
+            if let Some(to) = lock.resolve_dependency(path, dependency) {
+                graph.add_dependency(path, to);
+            }
+        }
+
+        materials.push(material);
+    }
+
+    graph.apply(&mut materials);
+
+    Ok(materials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Names of the materials of ids, for readable assertions of dependency graph.
+    fn names_of(materials: &[Material], ids: &[String]) -> Vec<String> {
+        ids.iter()
+            .map(|id| materials.iter().find(|m| m.id.as_ref() == Some(id)).map_or(id.clone(), |m| m.name.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn load_identity_tsv() {
+        let file = File::open("test/tsv/basic.tsv").unwrap();
+        let materials = load_materials(&file, &SourceType::Tsv, &LoadOptions::default()).unwrap();
+
+        let foo = &materials[0];
+        assert_eq!(foo.purl, Some("pkg:npm/%40heriet/foo@1.0.0".to_string()));
+        assert_eq!(foo.group, Some("@heriet".to_string()));
+        assert_eq!(foo.supplier, Some("heriet".to_string()));
+        assert_eq!(foo.material_type, Some("library".to_string()));
+        assert_eq!(foo.id, Some("foo@1.0.0".to_string()));
+
+        let bar = &materials[1];
+        assert_eq!(bar.purl, None);
+        assert_eq!(bar.annotations.get("usage"), Some(&"service".to_string()));
+    }
+
+    #[test]
+    fn load_identity_cyclonedx() {
+        for (path, source_type) in [
+            ("test/cyclonedx/graph.cdx.json", SourceType::CycloneDxJson),
+            ("test/cyclonedx/graph.cdx.xml", SourceType::CycloneDxXml),
+        ] {
+            let file = File::open(path).unwrap();
+            let materials = load_materials(&file, &source_type, &LoadOptions::default()).unwrap();
+            let material = |name: &str| materials.iter().find(|m| m.name == name).unwrap();
+
+            let foo = material("foo");
+            assert_eq!(foo.purl, Some("pkg:generic/heriet/foo@1.0.0".to_string()));
+            assert_eq!(foo.group, Some("heriet".to_string()));
+            assert_eq!(foo.supplier, Some("heriet".to_string()));
+            assert_eq!(foo.material_type, Some("library".to_string()));
+            assert_eq!(foo.id, Some("pkg:heriet/hatto/foo@1.0.0".to_string()));
+        }
+
+        // BOM without identity fields
+        let file = File::open("test/cyclonedx/basic.cdx.xml").unwrap();
+        let materials = load_materials(&file, &SourceType::CycloneDxXml, &LoadOptions::default()).unwrap();
+        assert_eq!(materials[0].name, "foo");
+        assert_eq!(materials[0].purl, None);
+        assert_eq!(materials[0].id, None);
+    }
+
+    #[test]
+    fn load_full_cyclonedx() {
+        let file = File::open("test/cyclonedx/full.cdx.json").unwrap();
+        let materials = load_materials(&file, &SourceType::CycloneDxJson, &LoadOptions::default()).unwrap();
+
+        let file = File::open("test/cyclonedx/full.cdx.xml").unwrap();
+        let xml_materials = load_materials(&file, &SourceType::CycloneDxXml, &LoadOptions::default()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&materials).unwrap(),
+            serde_json::to_value(&xml_materials).unwrap()
+        );
+
+        let foo = &materials[1];
+        assert_eq!(foo.licenses, vec!["MIT", "Apache-2.0"]);
+        assert_eq!(foo.cpe, Some("cpe:2.3:a:heriet:foo:1.0.0:*:*:*:*:*:*:*".to_string()));
+        assert_eq!(foo.author, Some("heriet, contributors".to_string()));
+        assert_eq!(foo.publisher, Some("heriet publisher".to_string()));
+        assert_eq!(foo.copyright, Some("Copyright (c) 2024 heriet".to_string()));
+        assert_eq!(
+            foo.hashes.get("SHA-256"),
+            Some(&"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".to_string())
+        );
+        assert_eq!(
+            foo.external_references[0],
+            ExternalReference {
+                reference_type: "vcs".to_string(),
+                url: "https://github.com/heriet/foo".to_string(),
+                comment: Some("source".to_string()),
+            }
+        );
+        assert_eq!(foo.annotations.get("usage"), Some(&"service".to_string()));
+        assert_eq!(
+            foo.annotations.get("cdx:npm:package:development"),
+            Some(&"false".to_string())
+        );
+
+        let foo_core = &materials[2];
+        assert_eq!(foo_core.name, "foo-core");
+        assert_eq!(foo_core.author, Some("heriet".to_string()));
+        assert_eq!(foo_core.licenses, vec!["MIT"]);
+
+        let bar = &materials[3];
+        assert_eq!(bar.licenses, vec!["BSD-3-Clause"]);
+    }
+
+    #[test]
+    fn load_license_sources() {
+        let file = File::open("test/cyclonedx/full.cdx.json").unwrap();
+        let mut materials = load_materials(&file, &SourceType::CycloneDxJson, &LoadOptions::default()).unwrap();
+
+        let foo = &materials[1];
+        assert_eq!(foo.declared_licenses, vec!["MIT OR Apache-2.0"]);
+        assert!(foo.concluded_licenses.is_empty());
+        assert!(foo.detected_licenses.is_empty());
+        assert_eq!(materials[2].concluded_licenses, vec!["MIT"]);
+        assert_eq!(materials[3].detected_licenses, vec!["BSD-3-Clause"]);
+
+        materials[1].select_licenses(&EffectiveLicenses::Detected);
+        assert!(materials[1].licenses.is_empty());
+        materials[1].select_licenses(&EffectiveLicenses::Declared);
+        assert_eq!(materials[1].license_expression.as_ref().unwrap().to_string(), "MIT OR Apache-2.0");
+
+        let file = File::open("test/spdx/basic.spdx.json").unwrap();
+        let materials = load_materials(&file, &SourceType::SpdxJson, &LoadOptions::default()).unwrap();
+
+        let bar = &materials[1];
+        assert_eq!(bar.declared_licenses, vec!["MIT OR Apache-2.0"]);
+        assert_eq!(bar.concluded_licenses, vec!["MIT OR Apache-2.0"]);
+    }
+
+    #[test]
+    fn spdx_license_precedence() {
+        let file = File::open("example/basic/basic.spdx").unwrap();
+        let materials = load_materials(&file, &SourceType::SpdxTag, &LoadOptions::default()).unwrap();
+        assert_eq!(materials[0].licenses, vec!["MIT"]);
+        assert_eq!(materials[1].licenses, vec!["MIT", "Apache-2.0"]);
+        assert_eq!(
+            materials[1].license_expression.as_ref().unwrap().to_string(),
+            "MIT OR Apache-2.0"
+        );
+
+        let licenses = |options: &LoadOptions| {
+            let file = File::open("test/spdx/license-source.spdx").unwrap();
+            load_materials(&file, &SourceType::SpdxTag, options)
+                .unwrap()
+                .into_iter()
+                .map(|material| (material.name, material.licenses))
+                .collect::<HashMap<_, _>>()
+        };
+
+        let materials = licenses(&LoadOptions::default());
+        assert_eq!(materials["concluded"], vec!["MIT"]);
+        assert_eq!(materials["declared"], vec!["Apache-2.0"]);
+        assert_eq!(materials["files"], vec!["MIT", "BSD-3-Clause"]);
+        assert_eq!(materials["none"], vec!["NONE"]);
+        // no licenses, which evaluate reports as an error in every mode
+        assert!(materials["noassertion"].is_empty());
+
+        let materials = licenses(&LoadOptions {
+            spdx_license_sources: vec![SpdxLicenseSource::Files, SpdxLicenseSource::Declared],
+            ..Default::default()
+        });
+        assert_eq!(materials["concluded"], vec!["BSD-3-Clause"]);
+        assert_eq!(materials["declared"], vec!["BSD-3-Clause"]);
+        assert_eq!(materials["files"], vec!["MIT", "BSD-3-Clause"]);
+        assert_eq!(materials["none"], vec!["MIT"]);
+        assert!(materials["noassertion"].is_empty());
+
+        let materials = licenses(&LoadOptions {
+            spdx_license_sources: vec![SpdxLicenseSource::Declared],
+            ..Default::default()
+        });
+        assert_eq!(materials["concluded"], vec!["Apache-2.0"]);
+        assert!(materials["files"].is_empty());
+    }
+
+    #[test]
+    fn load_spdx_files() {
+        let file = File::open("test/spdx/files.spdx").unwrap();
+        let materials = load_materials(&file, &SourceType::SpdxTag, &LoadOptions::default()).unwrap();
+        assert_eq!(materials.len(), 1);
+
+        let file = File::open("test/spdx/files.spdx").unwrap();
+        let options = LoadOptions {
+            spdx_files: true,
+            ..Default::default()
+        };
+        let materials = load_materials(&file, &SourceType::SpdxTag, &options).unwrap();
+        assert_eq!(materials.len(), 4);
+
+        let bar = &materials[2];
+        assert_eq!(bar.name, "./vendor/bar.c");
+        assert_eq!(bar.material_type, Some("file".to_string()));
+        assert_eq!(bar.file_path, Some("./vendor/bar.c".to_string()));
+        assert_eq!(bar.parent, Some("SPDXRef-Package-foo".to_string()));
+        assert_eq!(bar.licenses, vec!["GPL-2.0-only"]);
+        assert_eq!(bar.copyright, Some("Copyright (c) 2001 bar".to_string()));
+        assert_eq!(
+            bar.hashes.get("SHA1"),
+            Some(&"d6a770ba38583ed4bb4525bd96e50461655d2758".to_string())
+        );
+        assert_eq!(bar.paths_from_root, vec![vec!["SPDXRef-Package-foo", "SPDXRef-File-bar"]]);
+
+        let baz = &materials[3];
+        assert_eq!(baz.material_type, Some("snippet".to_string()));
+        assert_eq!(baz.file_path, Some("./src/foo.c".to_string()));
+        assert_eq!(baz.parent, Some("SPDXRef-File-foo".to_string()));
+        assert_eq!(baz.licenses, vec!["Apache-2.0"]);
+    }
+
+    #[test]
+    fn load_identity_spdx() {
+        let file = File::open("test/spdx/basic.spdx.json").unwrap();
+        let materials = load_materials(&file, &SourceType::SpdxJson, &LoadOptions::default()).unwrap();
+
+        let foo = &materials[0];
+        assert_eq!(foo.purl, Some("pkg:npm/%40heriet/foo@1.0.0".to_string()));
+        assert_eq!(foo.supplier, Some("heriet".to_string()));
+        assert_eq!(foo.material_type, Some("library".to_string()));
+        assert_eq!(foo.id, Some("SPDXRef-Package-foo".to_string()));
+
+        let bar = &materials[1];
+        assert_eq!(bar.purl, None);
+        assert_eq!(bar.supplier, None);
+        assert_eq!(bar.material_type, None);
+    }
+
+    #[test]
+    fn load_graph_cyclonedx() {
+        for (path, source_type) in [
+            ("test/cyclonedx/graph.cdx.json", SourceType::CycloneDxJson),
+            ("test/cyclonedx/graph.cdx.xml", SourceType::CycloneDxXml),
+        ] {
+            let file = File::open(path).unwrap();
+            let materials = load_materials(&file, &source_type, &LoadOptions::default()).unwrap();
+
+            let material = |name: &str| materials.iter().find(|m| m.name == name).unwrap();
+
+            let app = material("app");
+            assert!(app.is_project);
+            assert_eq!(names_of(&materials, &app.dependencies), vec!["foo"]);
+
+            let foo = material("foo");
+            assert!(!foo.is_project);
+            assert_eq!(foo.is_direct, Some(true));
+            assert_eq!(names_of(&materials, &foo.dependencies), vec!["bar"]);
+            assert_eq!(names_of(&materials, &foo.dependents), vec!["app"]);
+
+            let bar = material("bar");
+            assert_eq!(bar.is_direct, Some(false));
+            assert_eq!(names_of(&materials, &bar.paths_from_root[0]), vec!["app", "foo", "bar"]);
+        }
+    }
+
+    #[test]
+    fn load_cyclonedx_bom_references() {
+        let path = PathBuf::from("test/cyclonedx/assembly.cdx.json");
+        let names = |materials: &[Material]| materials.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+
+        let file = File::open(&path).unwrap();
+        let materials = load_materials(&file, &SourceType::CycloneDxJson, &LoadOptions::default()).unwrap();
+        assert_eq!(names(&materials), vec!["app", "lib-a", "lib-a-plugin", "lib-b"]);
+        assert_eq!(materials[1].children, vec!["lib-a-plugin"]);
+        assert_eq!(materials[2].parent, Some("lib-a".to_string()));
+
+        // assembly.cdx.json is referred again by lib-a.cdx.xml, but it is loaded once
+        let file = File::open(&path).unwrap();
+        let options = LoadOptions {
+            follow_bom_references: true,
+            source_path: Some(path.clone()),
+            ..Default::default()
+        };
+        let materials = load_materials(&file, &SourceType::CycloneDxJson, &options).unwrap();
+        assert_eq!(
+            names(&materials),
+            vec!["app", "lib-a", "lib-a-plugin", "lib-b", "lib-a-core", "zlib"]
+        );
+
+        let lib_a = &materials[1];
+        assert_eq!(lib_a.children, vec!["lib-a-plugin", "pkg:generic/lib-a-core@1.0.0"]);
+        assert_eq!(names_of(&materials, &lib_a.dependencies), vec!["lib-a-core"]);
+
+        let zlib = &materials[5];
+        assert_eq!(zlib.parent, Some("pkg:generic/lib-a-core@1.0.0".to_string()));
+        assert_eq!(
+            names_of(&materials, &zlib.paths_from_root[0]),
+            vec!["app", "lib-a", "lib-a-core", "zlib"]
+        );
     }
 
-    Ok(lis)
+    #[test]
+    fn load_cargo_metadata() {
+        let file = File::open("test/cargo/metadata.json").unwrap();
+        let materials = load_materials(&file, &SourceType::CargoMetadata, &LoadOptions::default()).unwrap();
+        let material = |name: &str| materials.iter().find(|m| m.name == name).unwrap();
+
+        // workspace members are roots and projects
+        let app = material("app");
+        assert!(app.is_project);
+        assert_eq!(app.material_type, Some("application".to_string()));
+        assert_eq!(app.scope, None);
+        assert_eq!(app.purl, None);
+        assert_eq!(app.author, Some("heriet <heriet@heriet.info>".to_string()));
+        assert_eq!(
+            names_of(&materials, &app.dependencies),
+            vec!["app-core", "serde", "pretty_assertions", "cc"]
+        );
+
+        let app_core = material("app-core");
+        assert!(app_core.is_project);
+        assert_eq!(app_core.declared_licenses, vec!["MIT OR Apache-2.0"]);
+        assert_eq!(app_core.licenses, vec!["MIT", "Apache-2.0"]);
+        assert_eq!(app_core.license_file, Some("/work/app/core/LICENSE".to_string()));
+
+        let serde = material("serde");
+        assert_eq!(serde.purl, Some("pkg:cargo/serde@1.0.200".to_string()));
+        assert_eq!(serde.scope, Some("required".to_string()));
+        assert_eq!(serde.dependency_kinds, vec!["normal"]);
+        assert!(!serde.is_project);
+        assert_eq!(serde.is_direct, Some(true));
+        assert_eq!(serde.external_references.len(), 3);
+
+        // dependencies of dev and build dependencies are not shipped either
+        let diff = material("diff");
+        assert_eq!(diff.scope, Some("excluded".to_string()));
+        assert_eq!(diff.dependency_kinds, vec!["dev"]);
+        assert_eq!(
+            names_of(&materials, &diff.paths_from_root[0]),
+            vec!["app", "pretty_assertions", "diff"]
+        );
+        assert_eq!(material("shlex").dependency_kinds, vec!["build"]);
+
+        assert_eq!(
+            material("fastrand").purl,
+            Some("pkg:cargo/fastrand@2.0.2?repository_url=https://crates.heriet.info/index/".to_string())
+        );
+    }
+
+    #[test]
+    fn load_npm_package_lock() {
+        let path = PathBuf::from("test/npm/package-lock.json");
+        let file = File::open(&path).unwrap();
+        let options = LoadOptions {
+            source_path: Some(path.clone()),
+            ..Default::default()
+        };
+        let materials = load_materials(&file, &SourceType::Npm, &options).unwrap();
+        let material = |id: &str| materials.iter().find(|m| m.id.as_deref() == Some(id)).unwrap();
+
+        let app = material("");
+        assert!(app.is_project);
+        assert_eq!(app.name, "app");
+        assert_eq!(
+            app.dependencies,
+            vec![
+                "node_modules/@heriet/bar",
+                "node_modules/foo",
+                "node_modules/git-dep",
+                "node_modules/tester",
+                "node_modules/native"
+            ]
+        );
+
+        // the nested one is required by foo, not by the project
+        let bar = material("node_modules/foo/node_modules/@heriet/bar");
+        assert_eq!(bar.name, "@heriet/bar");
+        assert_eq!(bar.purl, Some("pkg:npm/%40heriet/bar@1.0.0".to_string()));
+        assert_eq!(bar.scope, Some("required".to_string()));
+        assert_eq!(bar.is_direct, Some(false));
+        assert_eq!(
+            bar.paths_from_root,
+            vec![vec!["", "node_modules/foo", "node_modules/foo/node_modules/@heriet/bar"]]
+        );
+
+        assert_eq!(material("node_modules/tester").scope, Some("excluded".to_string()));
+        assert_eq!(material("node_modules/native").scope, Some("optional".to_string()));
+        assert_eq!(material("node_modules/react").scope, Some("optional".to_string()));
+
+        // license of legacy is only in node_modules
+        let legacy = material("node_modules/legacy");
+        assert_eq!(legacy.declared_licenses, vec!["MIT OR Apache-2.0"]);
+        assert_eq!(legacy.author, Some("heriet".to_string()));
+
+        // workspaces are roots, and their links are not materials
+        let ui = material("packages/ui");
+        assert_eq!(ui.name, "ui");
+        assert_eq!(ui.purl, None);
+        assert_eq!(ui.dependencies, vec!["node_modules/@heriet/bar"]);
+        assert!(materials.iter().all(|m| m.id.as_deref() != Some("node_modules/ui")));
+    }
+
+    #[test]
+    fn load_spdx_yaml() {
+        let file = File::open("test/spdx/basic.spdx.json").unwrap();
+        let expected = serde_json::to_value(load_materials(&file, &SourceType::SpdxJson, &LoadOptions::default()).unwrap()).unwrap();
+
+        // block style YAML with anchors, and JSON that is also YAML
+        for path in ["test/spdx/basic.spdx.yaml", "test/spdx/basic.spdx.json"] {
+            let file = File::open(path).unwrap();
+            let materials = load_materials(&file, &SourceType::SpdxYaml, &LoadOptions::default()).unwrap();
+            assert_eq!(serde_json::to_value(materials).unwrap(), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn load_spdx_rdf_and_xlsx() {
+        let file = File::open("test/spdx/basic.spdx.json").unwrap();
+        let expected = serde_json::to_value(load_materials(&file, &SourceType::SpdxJson, &LoadOptions::default()).unwrap()).unwrap();
+
+        for (path, source_type) in [
+            ("test/spdx/basic.spdx.rdf", SourceType::SpdxRdf),
+            ("test/spdx/basic.spdx.xlsx", SourceType::SpdxXlsx),
+        ] {
+            let file = File::open(path).unwrap();
+            let materials = load_materials(&file, &source_type, &LoadOptions::default()).unwrap();
+            assert_eq!(serde_json::to_value(materials).unwrap(), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn load_spdx3_json() {
+        let file = File::open("test/spdx/basic.spdx3.json").unwrap();
+        let materials = load_materials(&file, &SourceType::Spdx3Json, &LoadOptions::default()).unwrap();
+
+        let foo = &materials[0];
+        assert_eq!(foo.name, "foo");
+        assert_eq!(foo.version, Some("1.0.0".to_string()));
+        assert_eq!(foo.purl, Some("pkg:npm/%40heriet/foo@1.0.0".to_string()));
+        assert_eq!(foo.supplier, Some("heriet".to_string()));
+        assert_eq!(foo.material_type, Some("library".to_string()));
+        assert_eq!(
+            foo.id,
+            Some("https://github.com/heriet/hatto/test/spdx/basic#SPDXRef-Package-foo".to_string())
+        );
+        assert_eq!(names_of(&materials, &foo.dependencies), vec!["bar"]);
+        assert_eq!(foo.paths_from_root, vec![foo.id.clone().into_iter().collect::<Vec<_>>()]);
+
+        let bar = &materials[1];
+        assert_eq!(bar.name, "bar");
+        assert_eq!(bar.is_direct, Some(true));
+        assert_eq!(names_of(&materials, &bar.dependents), vec!["foo"]);
+    }
+
+    #[test]
+    fn load_graph_spdx() {
+        let file = File::open("test/spdx/basic.spdx.json").unwrap();
+        let materials = load_materials(&file, &SourceType::SpdxJson, &LoadOptions::default()).unwrap();
+
+        let foo = &materials[0];
+        assert_eq!(foo.is_direct, Some(false));
+        assert_eq!(foo.dependencies, vec!["SPDXRef-Package-bar"]);
+        assert_eq!(foo.paths_from_root, vec![vec!["SPDXRef-Package-foo"]]);
+
+        let bar = &materials[1];
+        assert_eq!(bar.is_direct, Some(true));
+        assert_eq!(bar.paths_from_root, vec![vec!["SPDXRef-Package-foo", "SPDXRef-Package-bar"]]);
+
+        let file = File::open("test/tsv/basic.tsv").unwrap();
+        let materials = load_materials(&file, &SourceType::Tsv, &LoadOptions::default()).unwrap();
+        assert_eq!(materials[0].is_direct, None);
+    }
 }
@@ -1,2 +1,5 @@
+pub mod graph;
+pub mod json;
 pub mod material;
-
+pub mod purl;
+pub mod result;
@@ -0,0 +1,213 @@
+use crate::error::Error;
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Parsed [package URL](https://github.com/package-url/purl-spec) e.g. `pkg:npm/%40angular/core@15.0.0`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct PackageUrl {
+    pub package_type: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub version: Option<String>,
+    pub qualifiers: BTreeMap<String, String>,
+    pub subpath: Option<String>,
+    purl: String,
+}
+
+impl PackageUrl {
+    pub fn parse(purl: &str) -> Result<Self, Error> {
+        let invalid = || Error::Purl(purl.to_string());
+
+        let (scheme, rest) = purl.trim().split_once(':').ok_or_else(invalid)?;
+        if !scheme.eq_ignore_ascii_case("pkg") {
+            return Err(invalid());
+        }
+        let rest = rest.trim_start_matches('/');
+
+        let (rest, subpath) = match rest.split_once('#') {
+            Some((rest, subpath)) => (rest, non_empty(decode_segments(subpath.trim_matches('/')))),
+            None => (rest, None),
+        };
+
+        let (rest, qualifiers) = match rest.split_once('?') {
+            Some((rest, qualifiers)) => (rest, parse_qualifiers(qualifiers)),
+            None => (rest, BTreeMap::new()),
+        };
+
+        let (rest, version) = match rest.rsplit_once('@') {
+            Some((rest, version)) => (rest, non_empty(percent_decode(version))),
+            None => (rest, None),
+        };
+
+        let (package_type, path) = rest.split_once('/').ok_or_else(invalid)?;
+        let path = path.trim_matches('/');
+        let (namespace, name) = match path.rsplit_once('/') {
+            Some((namespace, name)) => (non_empty(decode_segments(namespace)), percent_decode(name)),
+            None => (None, percent_decode(path)),
+        };
+
+        if package_type.is_empty() || name.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(PackageUrl {
+            package_type: package_type.to_lowercase(),
+            namespace,
+            name,
+            version,
+            qualifiers,
+            subpath,
+            purl: purl.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for PackageUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.purl)
+    }
+}
+
+/// Percent-encode a qualifier value (or a namespace, e.g. `@angular` to `%40angular`) of purl, keeping `:` and `/` of URL readable.
+pub fn encode_qualifier(value: &str) -> String {
+    let mut encoded = String::new();
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b':' | b'/' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+fn non_empty(s: String) -> Option<String> {
+    match s.is_empty() {
+        true => None,
+        false => Some(s),
+    }
+}
+
+fn decode_segments(path: &str) -> String {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(percent_decode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn parse_qualifiers(qualifiers: &str) -> BTreeMap<String, String> {
+    qualifiers
+        .split('&')
+        .filter_map(|kv| kv.split_once('='))
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| (key.to_lowercase(), percent_decode(value)))
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes.get(i..i + 3) {
+            Some([b'%', high, low]) => std::str::from_utf8(&[*high, *low])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl PackageUrl {
+    #[getter(type)]
+    fn py_type(&self) -> String {
+        self.package_type.clone()
+    }
+
+    #[getter]
+    fn namespace(&self) -> Option<String> {
+        self.namespace.clone()
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[getter]
+    fn version(&self) -> Option<String> {
+        self.version.clone()
+    }
+
+    #[getter]
+    fn qualifiers(&self) -> BTreeMap<String, String> {
+        self.qualifiers.clone()
+    }
+
+    #[getter]
+    fn subpath(&self) -> Option<String> {
+        self.subpath.clone()
+    }
+
+    fn __str__(&self) -> String {
+        self.purl.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PackageUrl({:?})", self.purl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_purl() {
+        let purl = PackageUrl::parse("pkg:npm/%40angular/core@15.0.0").unwrap();
+        assert_eq!(purl.package_type, "npm");
+        assert_eq!(purl.namespace, Some("@angular".to_string()));
+        assert_eq!(purl.name, "core");
+        assert_eq!(purl.version, Some("15.0.0".to_string()));
+
+        let purl = PackageUrl::parse("pkg:cargo/core@0.1.0").unwrap();
+        assert_eq!(purl.package_type, "cargo");
+        assert_eq!(purl.namespace, None);
+        assert_eq!(purl.name, "core");
+
+        let purl =
+            PackageUrl::parse("pkg:maven/org.apache.commons/io@1.3.4?classifier=sources&type=jar#src/main").unwrap();
+        assert_eq!(purl.namespace, Some("org.apache.commons".to_string()));
+        assert_eq!(purl.qualifiers.get("classifier"), Some(&"sources".to_string()));
+        assert_eq!(purl.subpath, Some("src/main".to_string()));
+        assert_eq!(
+            purl.to_string(),
+            "pkg:maven/org.apache.commons/io@1.3.4?classifier=sources&type=jar#src/main"
+        );
+
+        assert!(PackageUrl::parse("npm/foo").is_err());
+        assert!(PackageUrl::parse("pkg:npm").is_err());
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone)]
+#[cfg_attr(feature = "python", pyclass)]
+pub struct EvaluateResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl EvaluateResult {
+    pub fn new() -> Self {
+        EvaluateResult {
+            success: true,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn add_error(&mut self, message: String) {
+        self.errors.push(message);
+        self.success = false;
+    }
+
+    pub fn add_warning(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl EvaluateResult {
+    #[new]
+    fn py_new() -> Self {
+        EvaluateResult::new()
+    }
+
+    #[getter]
+    fn success(&self) -> bool {
+        self.success
+    }
+
+    #[getter]
+    fn errors(&self) -> Vec<String> {
+        self.errors.clone()
+    }
+
+    #[getter]
+    fn warnings(&self) -> Vec<String> {
+        self.warnings.clone()
+    }
+
+    #[pyo3(name = "add_error")]
+    fn py_add_error(&mut self, message: String) {
+        self.add_error(message);
+    }
+
+    #[pyo3(name = "add_warning")]
+    fn py_add_warning(&mut self, message: String) {
+        self.add_warning(message);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::error::Error;
+use crate::model::purl::encode_qualifier;
+use crate::npm::license_of;
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{BufReader, Read};
+
+/// Registry of npm. Packages resolved from it have no qualifier in purl.
+const NPM_REGISTRY: &str = "https://registry.npmjs.org/";
+
+/// `package-lock.json` (or `npm-shrinkwrap.json`) of lockfile version 2 or 3.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageLock {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub lockfile_version: u32,
+    /// Keys are paths from the project, e.g. `node_modules/@heriet/foo`, and `""` is the project itself.
+    #[serde(default)]
+    pub packages: BTreeMap<String, LockedPackage>,
+    // other field is umimplemented
+}
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedPackage {
+    /// Set only if it differs from the path, e.g. aliases and workspaces.
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// URL of tarball, or the target path of link.
+    pub resolved: Option<String>,
+    pub license: Option<Value>,
+    #[serde(default)]
+    pub link: bool,
+    #[serde(default)]
+    pub dev: bool,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default)]
+    pub dev_optional: bool,
+    #[serde(default)]
+    pub peer: bool,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub dev_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub optional_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub peer_dependencies: BTreeMap<String, String>,
+    // other field is umimplemented
+}
+
+impl PackageLock {
+    /// Name of the package at the path, e.g. `@heriet/bar` of `node_modules/foo/node_modules/@heriet/bar`.
+    pub fn package_name(&self, path: &str) -> Option<String> {
+        let package = self.packages.get(path)?;
+        if let Some(name) = &package.name {
+            return Some(name.clone());
+        }
+
+        match path.rsplit_once("node_modules/") {
+            Some((_, name)) => Some(name.to_string()),
+            None if path.is_empty() => self.name.clone(),
+            None => path.rsplit('/').next().map(|name| name.to_string()),
+        }
+    }
+
+    /// Path of the package required by `name` from the package at `from`, searched in `node_modules` of the package
+    /// and its ancestors in the same way as node. Links are followed to their targets, e.g. workspaces.
+    pub fn resolve_dependency(&self, from: &str, name: &str) -> Option<&str> {
+        let mut dir = from;
+        loop {
+            let candidate = match dir.is_empty() {
+                true => format!("node_modules/{}", name),
+                false => format!("{}/node_modules/{}", dir, name),
+            };
+            if let Some((path, package)) = self.packages.get_key_value(&candidate) {
+                return match package.link {
+                    true => package
+                        .resolved
+                        .as_deref()
+                        .and_then(|target| self.packages.get_key_value(target))
+                        .map(|(target, _)| target.as_str()),
+                    false => Some(path.as_str()),
+                };
+            }
+
+            if dir.is_empty() {
+                return None;
+            }
+            dir = dir.rsplit_once('/').map(|(parent, _)| parent).unwrap_or("");
+        }
+    }
+
+    /// Workspaces and other local packages, i.e. packages not installed into `node_modules`.
+    pub fn is_local(path: &str) -> bool {
+        !path.starts_with("node_modules/") && !path.contains("/node_modules/")
+    }
+}
+
+impl LockedPackage {
+    /// Packages only for development are `excluded`, and optional or peer packages, which may be missing at runtime,
+    /// are `optional`.
+    pub fn scope(&self) -> &str {
+        match (self.dev, self.optional || self.dev_optional || self.peer) {
+            (true, _) => "excluded",
+            (false, true) => "optional",
+            (false, false) => "required",
+        }
+    }
+
+    pub fn license(&self) -> Option<String> {
+        self.license.as_ref().and_then(license_of)
+    }
+
+    /// Names of all dependencies, including `devDependencies` of the project and workspaces.
+    pub fn dependency_names(&self) -> impl Iterator<Item = &String> {
+        self.dependencies
+            .keys()
+            .chain(self.dev_dependencies.keys())
+            .chain(self.optional_dependencies.keys())
+            .chain(self.peer_dependencies.keys())
+    }
+
+    /// `pkg:npm` purl. Packages resolved from other than the npm registry are given by `download_url` or `vcs_url`,
+    /// and local packages (`file:`) have no purl.
+    pub fn purl(&self, name: &str) -> Option<String> {
+        let version = self.version.as_deref()?;
+        let purl = match name.split_once('/') {
+            Some((namespace, name)) if namespace.starts_with('@') => {
+                format!(
+                    "pkg:npm/{}/{}@{}",
+                    encode_qualifier(namespace),
+                    name,
+                    version
+                )
+            }
+            _ => format!("pkg:npm/{}@{}", name, version),
+        };
+
+        match self.resolved.as_deref() {
+            None => Some(purl),
+            Some(resolved) if resolved.starts_with(NPM_REGISTRY) => Some(purl),
+            Some(resolved) if resolved.starts_with("git+") || resolved.starts_with("git://") => {
+                Some(format!("{}?vcs_url={}", purl, encode_qualifier(resolved)))
+            }
+            Some(resolved)
+                if resolved.starts_with("https://") || resolved.starts_with("http://") =>
+            {
+                Some(format!(
+                    "{}?download_url={}",
+                    purl,
+                    encode_qualifier(resolved)
+                ))
+            }
+            Some(_) => None,
+        }
+    }
+}
+
+pub fn load_package_lock<R: Read>(reader: R) -> Result<PackageLock, Error> {
+    let lock: PackageLock = serde_json::from_reader(BufReader::new(reader))?;
+
+    // lockfile version 1 has only `dependencies`, which lacks licenses
+    if lock.lockfile_version < 2 || lock.packages.is_empty() {
+        return Err(Error::Failure(format!(
+            "lockfileVersion {} of package-lock.json is not supported, update it by npm 7 or later",
+            lock.lockfile_version
+        )));
+    }
+
+    Ok(lock)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn load_package_lock_valid() {
+        let file = File::open("test/npm/package-lock.json").unwrap();
+        let lock = load_package_lock(&file).unwrap();
+
+        assert_eq!(lock.lockfile_version, 3);
+        assert_eq!(lock.package_name(""), Some("app".to_string()));
+        assert_eq!(
+            lock.package_name("node_modules/foo/node_modules/@heriet/bar"),
+            Some("@heriet/bar".to_string())
+        );
+
+        // nested one is found first, and links are followed to workspaces
+        assert_eq!(
+            lock.resolve_dependency("node_modules/foo", "@heriet/bar"),
+            Some("node_modules/foo/node_modules/@heriet/bar")
+        );
+        assert_eq!(
+            lock.resolve_dependency("packages/ui", "@heriet/bar"),
+            Some("node_modules/@heriet/bar")
+        );
+        assert_eq!(lock.resolve_dependency("", "ui"), Some("packages/ui"));
+        assert_eq!(lock.resolve_dependency("", "missing"), None);
+    }
+
+    #[test]
+    fn reject_lockfile_version_1() {
+        let lock = r#"{"name": "app", "lockfileVersion": 1, "dependencies": {}}"#;
+
+        assert!(load_package_lock(lock.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn npm_purl() {
+        let package = |resolved: Option<&str>| LockedPackage {
+            version: Some("1.0.0".to_string()),
+            resolved: resolved.map(|resolved| resolved.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            package(Some(
+                "https://registry.npmjs.org/@heriet/bar/-/bar-1.0.0.tgz"
+            ))
+            .purl("@heriet/bar"),
+            Some("pkg:npm/%40heriet/bar@1.0.0".to_string())
+        );
+        assert_eq!(
+            package(Some("https://npm.heriet.info/foo/-/foo-1.0.0.tgz")).purl("foo"),
+            Some(
+                "pkg:npm/foo@1.0.0?download_url=https://npm.heriet.info/foo/-/foo-1.0.0.tgz"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            package(Some("git+ssh://git@github.com/heriet/foo.git#0123abc")).purl("foo"),
+            Some(
+                "pkg:npm/foo@1.0.0?vcs_url=git%2Bssh://git%40github.com/heriet/foo.git%230123abc"
+                    .to_string()
+            )
+        );
+        assert_eq!(package(Some("file:../foo")).purl("foo"), None);
+    }
+}
@@ -0,0 +1,36 @@
+mod lockfile;
+mod package;
+
+pub use lockfile::{load_package_lock, PackageLock};
+pub use package::load_installed_package_json;
+
+use serde_json::Value;
+
+/// License of `license` or legacy `licenses` field, which is a string, an object of `type` and `url`, or an array of
+/// them. Licenses of an array are choices.
+fn license_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(license) => {
+            Some(license.trim().to_string()).filter(|license| !license.is_empty())
+        }
+        Value::Object(license) => license.get("type").and_then(license_of),
+        Value::Array(licenses) => {
+            let licenses = licenses.iter().filter_map(license_of).collect::<Vec<_>>();
+            match licenses.len() {
+                0 => None,
+                1 => licenses.into_iter().next(),
+                _ => Some(
+                    licenses
+                        .iter()
+                        .map(|license| match license.contains(' ') {
+                            true => format!("({})", license),
+                            false => license.clone(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" OR "),
+                ),
+            }
+        }
+        _ => None,
+    }
+}
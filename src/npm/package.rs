@@ -0,0 +1,92 @@
+use crate::error::Error;
+use crate::npm::license_of;
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// `package.json` of installed package. Only the fields lacking in `package-lock.json` are read.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+pub struct PackageJson {
+    pub license: Option<Value>,
+    /// Deprecated form of `license`, e.g. `[{"type": "MIT", "url": "..."}]`.
+    pub licenses: Option<Value>,
+    pub author: Option<Value>,
+    pub homepage: Option<String>,
+    pub repository: Option<Value>,
+    // other field is umimplemented
+}
+
+impl PackageJson {
+    pub fn license(&self) -> Option<String> {
+        self.license
+            .as_ref()
+            .and_then(license_of)
+            .or_else(|| self.licenses.as_ref().and_then(license_of))
+    }
+
+    /// `author` is either `Name <email> (url)` or an object of `name`, `email` and `url`.
+    pub fn author(&self) -> Option<String> {
+        match self.author.as_ref()? {
+            Value::String(author) => Some(author.clone()),
+            Value::Object(author) => author
+                .get("name")
+                .and_then(Value::as_str)
+                .map(|name| name.to_string()),
+            _ => None,
+        }
+    }
+
+    /// `repository` is either an URL or an object of `type` and `url`.
+    pub fn repository(&self) -> Option<String> {
+        match self.repository.as_ref()? {
+            Value::String(repository) => Some(repository.clone()),
+            Value::Object(repository) => repository
+                .get("url")
+                .and_then(Value::as_str)
+                .map(|url| url.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Load `package.json` in the directory of installed package. `None` if it is not installed.
+pub fn load_installed_package_json(dir: &Path) -> Result<Option<PackageJson>, Error> {
+    let path = dir.join("package.json");
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let package_json = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+
+    Ok(Some(package_json))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn load_legacy_licenses() {
+        let package_json = load_installed_package_json(Path::new("test/npm/node_modules/legacy"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            package_json.license(),
+            Some("MIT OR Apache-2.0".to_string())
+        );
+        assert_eq!(package_json.author(), Some("heriet".to_string()));
+        assert_eq!(
+            package_json.repository(),
+            Some("git+https://github.com/heriet/legacy.git".to_string())
+        );
+
+        assert_eq!(
+            load_installed_package_json(Path::new("test/npm/node_modules/missing")).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use crate::error::Error;
+use crate::license::compatibility::Compatibility;
+use crate::license::LicenseExpression;
+use crate::model::material::Material;
+use crate::model::result::EvaluateResult;
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Policy written in TOML or YAML, evaluated without Python.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DeclarativePolicy {
+    #[serde(default)]
+    pub allowed: Vec<String>,
+
+    #[serde(default)]
+    pub denied: Vec<String>,
+
+    #[serde(default)]
+    pub warn: Vec<String>,
+
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+
+    #[serde(default)]
+    pub overrides: Vec<Override>,
+
+    /// Reports detected licenses that are not in declared licenses.
+    pub undeclared_licenses: Option<Severity>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warn,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LicenseLists<'a> {
+    allowed: &'a [String],
+    denied: &'a [String],
+    warn: &'a [String],
+}
+
+/// Licenses applied to materials that have all of the annotations.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub annotations: HashMap<String, String>,
+
+    #[serde(default)]
+    pub allowed: Vec<String>,
+
+    #[serde(default)]
+    pub denied: Vec<String>,
+
+    #[serde(default)]
+    pub warn: Vec<String>,
+}
+
+/// Licenses applied to a material by name and optional version.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Override {
+    pub name: String,
+
+    pub version: Option<String>,
+
+    #[serde(default)]
+    pub ignore: bool,
+
+    #[serde(default)]
+    pub allowed: Vec<String>,
+
+    #[serde(default)]
+    pub denied: Vec<String>,
+
+    #[serde(default)]
+    pub warn: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Verdict {
+    Denied,
+    NotAllowed,
+    Warn,
+    Allowed,
+}
+
+impl LicenseLists<'_> {
+    fn verdict(&self, license: &str) -> Option<Verdict> {
+        if contains_license(self.denied, license) {
+            Some(Verdict::Denied)
+        } else if contains_license(self.warn, license) {
+            Some(Verdict::Warn)
+        } else if contains_license(self.allowed, license) {
+            Some(Verdict::Allowed)
+        } else {
+            None
+        }
+    }
+}
+
+impl Override {
+    fn matches(&self, material: &Material) -> bool {
+        self.name == material.name && (self.version.is_none() || self.version == material.version)
+    }
+
+    fn lists(&self) -> LicenseLists<'_> {
+        LicenseLists {
+            allowed: &self.allowed,
+            denied: &self.denied,
+            warn: &self.warn,
+        }
+    }
+}
+
+impl Rule {
+    fn matches(&self, material: &Material) -> bool {
+        self.annotations
+            .iter()
+            .all(|(key, value)| material.annotations.get(key) == Some(value))
+    }
+
+    fn lists(&self) -> LicenseLists<'_> {
+        LicenseLists {
+            allowed: &self.allowed,
+            denied: &self.denied,
+            warn: &self.warn,
+        }
+    }
+}
+
+impl DeclarativePolicy {
+    pub fn from_toml(content: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn from_yaml(content: &str) -> Result<Self, Error> {
+        Ok(serde_yaml::from_str(content)?)
+    }
+
+    /// Evaluate material. License lists are consulted from the most specific one: matched overrides,
+    /// matched rules and top level lists. The first lists that mention the license decide.
+    ///
+    /// If the project license is given, licenses of material must be also compatible with it.
+    pub fn evaluate(
+        &self,
+        material: &Material,
+        project_license: Option<(&LicenseExpression, &Compatibility)>,
+    ) -> EvaluateResult {
+        let mut result = EvaluateResult::new();
+
+        let overrides = self.overrides.iter().filter(|o| o.matches(material)).collect::<Vec<_>>();
+        if overrides.iter().any(|o| o.ignore) {
+            return result;
+        }
+
+        let mut layers: Vec<LicenseLists> = overrides.iter().map(|o| o.lists()).collect();
+        layers.extend(self.rules.iter().filter(|r| r.matches(material)).map(|r| r.lists()));
+        layers.push(LicenseLists {
+            allowed: &self.allowed,
+            denied: &self.denied,
+            warn: &self.warn,
+        });
+
+        let allow_list_only = layers.iter().any(|layer| !layer.allowed.is_empty());
+        let verdict = |license: &str| -> Verdict {
+            layers
+                .iter()
+                .find_map(|layer| layer.verdict(license))
+                .unwrap_or(match allow_list_only {
+                    true => Verdict::NotAllowed,
+                    false => Verdict::Allowed,
+                })
+        };
+
+        let alternatives = match &material.license_expression {
+            Some(expression) => expression.alternative_ids(),
+            None => vec![material.licenses.clone()],
+        };
+
+        // choose the alternative that has the best verdict
+        let best = alternatives
+            .iter()
+            .map(|licenses| {
+                let verdicts = licenses
+                    .iter()
+                    .map(|license| (license, verdict(license)))
+                    .collect::<Vec<_>>();
+                let worst = verdicts
+                    .iter()
+                    .map(|(_, v)| *v)
+                    .fold(Verdict::Allowed, |a, b| if b < a { b } else { a });
+                (worst, verdicts)
+            })
+            .fold(None, |best: Option<(Verdict, Vec<(&String, Verdict)>)>, current| match best {
+                Some(best) if best.0 >= current.0 => Some(best),
+                _ => Some(current),
+            });
+
+        if let Some((_, verdicts)) = best {
+            for (license, verdict) in verdicts {
+                match verdict {
+                    Verdict::Denied => result.add_error(format!("{} is denied", license)),
+                    Verdict::NotAllowed => result.add_error(format!("{} is not allowed", license)),
+                    Verdict::Warn => result.add_warning(format!("{} is allowed with warning", license)),
+                    Verdict::Allowed => (),
+                }
+            }
+        }
+
+        if let Some(severity) = self.undeclared_licenses {
+            for license in undeclared_licenses(material) {
+                let message = format!("{} is detected but not declared", license);
+                match severity {
+                    Severity::Error => result.add_error(message),
+                    Severity::Warn => result.add_warning(message),
+                }
+            }
+        }
+
+        if let Some((project_license, compatibility)) = project_license {
+            if let Some(message) = compatibility.check_material(material, project_license) {
+                result.add_error(message);
+            }
+        }
+
+        result
+    }
+}
+
+/// Detected licenses that no declared license mentions. Nothing is reported if either of them is empty, since
+/// many sources have only one of them.
+fn undeclared_licenses(material: &Material) -> Vec<&String> {
+    if material.declared_licenses.is_empty() {
+        return vec![];
+    }
+
+    let declared = material
+        .declared_licenses
+        .iter()
+        .flat_map(|license| match LicenseExpression::parse(license) {
+            Ok(expression) => expression.license_ids(),
+            Err(_) => vec![license.clone()],
+        })
+        .collect::<Vec<_>>();
+
+    material
+        .detected_licenses
+        .iter()
+        .filter(|license| !contains_license(&declared, license))
+        .collect()
+}
+
+/// `X WITH E` and `X+` are matched by `X` too.
+fn contains_license(list: &[String], license: &str) -> bool {
+    if list.iter().any(|l| l == license) {
+        return true;
+    }
+
+    match LicenseExpression::parse(license) {
+        Ok(expression) => match expression.simple_licenses().as_slice() {
+            [simple] => list
+                .iter()
+                .any(|l| *l == simple.to_string() || (simple.or_later && *l == simple.identifier)),
+            _ => false,
+        },
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn material(name: &str, licenses: &str, annotations: &[(&str, &str)]) -> Material {
+        let mut material = Material {
+            name: name.to_string(),
+            version: Some("1.0.0".to_string()),
+            annotations: annotations
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        material.set_license_strings(vec![licenses.to_string()]);
+        material
+    }
+
+    const POLICY: &str = r#"
+allowed = ["MIT", "Apache-2.0", "GPL-2.0-only"]
+denied = ["AGPL-3.0-only"]
+warn = ["LGPL-2.1-only"]
+
+[[rules]]
+annotations = { usage = "service" }
+allowed = ["AGPL-3.0-only"]
+
+[[overrides]]
+name = "foo"
+version = "1.0.0"
+ignore = true
+"#;
+
+    #[test]
+    fn evaluate_lists() {
+        let policy = DeclarativePolicy::from_toml(POLICY).unwrap();
+
+        assert!(policy.evaluate(&material("a", "MIT", &[]), None).success);
+
+        let result = policy.evaluate(&material("a", "AGPL-3.0-only", &[]), None);
+        assert_eq!(result.errors, vec!["AGPL-3.0-only is denied"]);
+
+        let result = policy.evaluate(&material("a", "UNKNOWN", &[]), None);
+        assert_eq!(result.errors, vec!["UNKNOWN is not allowed"]);
+
+        let result = policy.evaluate(&material("a", "LGPL-2.1-only", &[]), None);
+        assert!(result.success);
+        assert_eq!(result.warnings, vec!["LGPL-2.1-only is allowed with warning"]);
+
+        assert!(policy
+            .evaluate(&material("a", "GPL-2.0-only WITH Classpath-exception-2.0", &[]), None)
+            .success);
+    }
+
+    #[test]
+    fn evaluate_expression() {
+        let policy = DeclarativePolicy::from_toml(POLICY).unwrap();
+
+        assert!(policy.evaluate(&material("a", "MIT OR AGPL-3.0-only", &[]), None).success);
+        assert!(!policy.evaluate(&material("a", "MIT AND AGPL-3.0-only", &[]), None).success);
+    }
+
+    #[test]
+    fn evaluate_rules_and_overrides() {
+        let policy = DeclarativePolicy::from_toml(POLICY).unwrap();
+
+        assert!(policy
+            .evaluate(&material("a", "AGPL-3.0-only", &[("usage", "service")]), None)
+            .success);
+        assert!(policy.evaluate(&material("foo", "UNKNOWN", &[]), None).success);
+    }
+
+    #[test]
+    fn project_license() {
+        let policy = DeclarativePolicy::from_toml(POLICY).unwrap();
+        let compatibility = Compatibility::new(crate::license::category::Classifier::new().unwrap()).unwrap();
+        let project_license = LicenseExpression::parse("Apache-2.0").unwrap();
+        let project = Some((&project_license, &compatibility));
+
+        assert!(policy.evaluate(&material("a", "MIT", &[]), project).success);
+
+        let result = policy.evaluate(&material("a", "GPL-2.0-only", &[]), project);
+        assert_eq!(
+            result.errors,
+            vec!["GPL-2.0-only is not compatible with project license Apache-2.0"]
+        );
+    }
+
+    #[test]
+    fn undeclared_licenses() {
+        let policy = DeclarativePolicy::from_toml("undeclared_licenses = \"warn\"\n").unwrap();
+        let mut foo = material("foo", "MIT", &[]);
+        foo.declared_licenses = vec!["MIT OR Apache-2.0".to_string()];
+        foo.detected_licenses = vec!["Apache-2.0".to_string(), "GPL-2.0-only WITH Classpath-exception-2.0".to_string()];
+
+        let result = policy.evaluate(&foo, None);
+        assert!(result.success);
+        assert_eq!(
+            result.warnings,
+            vec!["GPL-2.0-only WITH Classpath-exception-2.0 is detected but not declared"]
+        );
+
+        let policy = DeclarativePolicy::from_yaml("undeclared_licenses: error\n").unwrap();
+        assert!(!policy.evaluate(&foo, None).success);
+
+        foo.declared_licenses = vec![];
+        assert!(policy.evaluate(&foo, None).success);
+    }
+
+    #[test]
+    fn deny_list_only() {
+        let policy = DeclarativePolicy::from_yaml("denied:\n  - GPL-3.0-only\n").unwrap();
+
+        assert!(policy.evaluate(&material("a", "UNKNOWN", &[]), None).success);
+        assert!(!policy.evaluate(&material("a", "GPL-3.0-only", &[]), None).success);
+    }
+}
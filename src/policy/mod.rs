@@ -0,0 +1,3 @@
+mod declarative;
+
+pub use declarative::DeclarativePolicy;
@@ -0,0 +1,341 @@
+use crate::license::category::Classifier;
+use crate::license::compatibility::Compatibility;
+use crate::license::list::{license_list, ExceptionInfo, LicenseInfo};
+use crate::license::LicenseExpression;
+use crate::model::material::Material;
+use crate::model::purl::PackageUrl;
+use crate::model::result::EvaluateResult;
+
+use pyo3::exceptions::{PyAttributeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::AsPyPointer;
+use std::collections::HashMap;
+
+#[pyfunction]
+#[pyo3(name = "get")]
+fn licenses_get(id: &str) -> Option<LicenseInfo> {
+    license_list().get(id).cloned()
+}
+
+#[pyfunction]
+#[pyo3(name = "get_exception")]
+fn licenses_get_exception(id: &str) -> Option<ExceptionInfo> {
+    license_list().get_exception(id).cloned()
+}
+
+#[pyfunction]
+#[pyo3(name = "ids")]
+fn licenses_ids() -> Vec<String> {
+    license_list().licenses.iter().map(|license| license.id.clone()).collect()
+}
+
+#[pyfunction]
+#[pyo3(name = "exception_ids")]
+fn licenses_exception_ids() -> Vec<String> {
+    license_list()
+        .exceptions
+        .iter()
+        .map(|exception| exception.id.clone())
+        .collect()
+}
+
+/// Passed to `evaluate_all(materials, report)` of the policy to attach errors and warnings to materials or
+/// to the whole document.
+#[pyclass]
+struct Report {
+    /// Held so that the pointers in `positions` stay valid.
+    #[allow(dead_code)]
+    materials: Vec<Py<Material>>,
+    /// Position of each material in `materials` and `results`, keyed by its pointer.
+    positions: HashMap<usize, usize>,
+    results: Vec<Py<EvaluateResult>>,
+    document: Py<EvaluateResult>,
+}
+
+impl Report {
+    fn new(materials: Vec<Py<Material>>, results: Vec<Py<EvaluateResult>>, document: Py<EvaluateResult>) -> Self {
+        let positions = materials
+            .iter()
+            .enumerate()
+            .map(|(i, material)| (material.as_ptr() as usize, i))
+            .collect();
+
+        Report {
+            materials,
+            positions,
+            results,
+            document,
+        }
+    }
+
+    fn target(&self, py: Python, material: Option<&PyAny>) -> PyResult<Py<EvaluateResult>> {
+        let material = match material {
+            Some(material) => material,
+            None => return Ok(self.document.clone_ref(py)),
+        };
+
+        match self.positions.get(&(material.as_ptr() as usize)) {
+            Some(&i) => Ok(self.results[i].clone_ref(py)),
+            None => Err(PyValueError::new_err("material is not in the report")),
+        }
+    }
+}
+
+#[pymethods]
+impl Report {
+    /// Document-level result.
+    #[getter]
+    fn document(&self, py: Python) -> Py<EvaluateResult> {
+        self.document.clone_ref(py)
+    }
+
+    fn result_of(&self, py: Python, material: &PyAny) -> PyResult<Py<EvaluateResult>> {
+        self.target(py, Some(material))
+    }
+
+    #[args(material = "None")]
+    fn add_error(&self, py: Python, message: String, material: Option<&PyAny>) -> PyResult<()> {
+        self.target(py, material)?.borrow_mut(py).add_error(message);
+        Ok(())
+    }
+
+    #[args(material = "None")]
+    fn add_warning(&self, py: Python, message: String, material: Option<&PyAny>) -> PyResult<()> {
+        self.target(py, material)?.borrow_mut(py).add_warning(message);
+        Ok(())
+    }
+}
+
+/// Values provided by the `hatto` module.
+pub struct HattoModule {
+    pub classifier: Classifier,
+    pub compatibility: Compatibility,
+    pub project_license: Option<LicenseExpression>,
+    pub project: Option<Material>,
+}
+
+/// Register the `hatto` module to `sys.modules` so that policies and curations can `import hatto`.
+fn init_hatto_module(py: Python, module: &HattoModule) -> PyResult<()> {
+    let hatto = PyModule::new(py, "hatto")?;
+    hatto.add_class::<LicenseExpression>()?;
+    hatto.add_class::<PackageUrl>()?;
+
+    let classifier = Py::new(py, module.classifier.clone())?;
+    hatto.add("category_of", classifier.getattr(py, "category_of")?)?;
+
+    let compatibility = Py::new(py, module.compatibility.clone())?;
+    hatto.add("is_compatible", compatibility.getattr(py, "is_compatible")?)?;
+    hatto.add("project_license", module.project_license.clone().into_py(py))?;
+    hatto.add("project", module.project.clone().into_py(py))?;
+
+    let licenses = PyModule::new(py, "licenses")?;
+    licenses.add("version", &license_list().version)?;
+    licenses.add_class::<LicenseInfo>()?;
+    licenses.add_class::<ExceptionInfo>()?;
+    licenses.add_function(wrap_pyfunction!(licenses_get, licenses)?)?;
+    licenses.add_function(wrap_pyfunction!(licenses_get_exception, licenses)?)?;
+    licenses.add_function(wrap_pyfunction!(licenses_ids, licenses)?)?;
+    licenses.add_function(wrap_pyfunction!(licenses_exception_ids, licenses)?)?;
+    hatto.add_submodule(licenses)?;
+
+    let modules: &PyDict = py.import("sys")?.getattr("modules")?.downcast()?;
+    modules.set_item("hatto", hatto)?;
+    modules.set_item("hatto.licenses", licenses)?;
+
+    Ok(())
+}
+
+/// Execute python code as a new module. A module of the same name is not reused, so that definitions of
+/// another policy or curation do not remain.
+fn load_module<'py>(py: Python<'py>, code: &str, module_name: &str) -> PyResult<&'py PyModule> {
+    let modules: &PyDict = py.import("sys")?.getattr("modules")?.downcast()?;
+    if modules.contains(module_name)? {
+        modules.del_item(module_name)?;
+    }
+
+    PyModule::from_code(py, code, "", module_name)
+}
+
+/// Call `curate_material(material)` of the curation for every material.
+pub fn curate_materials(
+    curation_py: &str,
+    materials: Vec<Material>,
+    module: &HattoModule,
+) -> PyResult<Vec<Material>> {
+    Python::with_gil(|py| {
+        init_hatto_module(py, module)?;
+
+        let curation_module = load_module(py, curation_py, "hatto_curation")?;
+        let curate: Py<PyAny> = curation_module.getattr("curate_material")?.into();
+
+        let mut curated_materials: Vec<Material> = Vec::new();
+        for material in materials {
+            let py_material = Py::new(py, material)?;
+            curate.call1(py, (&py_material,))?;
+            curated_materials.push(py_material.extract(py)?);
+        }
+
+        Ok(curated_materials)
+    })
+}
+
+/// Call `evaluate(material, result)` of the policy for every material, and then `evaluate_all(materials, report)`
+/// once with all materials. Either of them can be omitted. Returns results of materials and the document.
+pub fn evaluate_materials(
+    policy_py: &str,
+    materials: &[Material],
+    module: &HattoModule,
+) -> PyResult<(Vec<EvaluateResult>, EvaluateResult)> {
+    Python::with_gil(|py| {
+        init_hatto_module(py, module)?;
+
+        let policy_module = load_module(py, policy_py, "hatto_policy")?;
+        let evaluate = policy_module.getattr("evaluate").ok();
+        let evaluate_all = policy_module.getattr("evaluate_all").ok();
+        if evaluate.is_none() && evaluate_all.is_none() {
+            return Err(PyAttributeError::new_err(
+                "policy must implement evaluate(material, result) or evaluate_all(materials, report)",
+            ));
+        }
+
+        let mut py_materials: Vec<Py<Material>> = Vec::new();
+        let mut py_results: Vec<Py<EvaluateResult>> = Vec::new();
+        for material in materials {
+            let py_material = Py::new(py, material.clone())?;
+            let py_evaluate_result = Py::new(py, EvaluateResult::new())?;
+            if let Some(evaluate) = evaluate {
+                evaluate.call1((&py_material, &py_evaluate_result))?;
+            }
+            py_materials.push(py_material);
+            py_results.push(py_evaluate_result);
+        }
+
+        let document = Py::new(py, EvaluateResult::new())?;
+        if let Some(evaluate_all) = evaluate_all {
+            let report = Report::new(
+                py_materials.iter().map(|m| m.clone_ref(py)).collect(),
+                py_results.iter().map(|r| r.clone_ref(py)).collect(),
+                document.clone_ref(py),
+            );
+            evaluate_all.call1((py_materials, Py::new(py, report)?))?;
+        }
+
+        let evaluate_results = py_results
+            .iter()
+            .map(|result| result.extract(py))
+            .collect::<PyResult<Vec<EvaluateResult>>>()?;
+        let document_result: EvaluateResult = document.extract(py)?;
+
+        Ok((evaluate_results, document_result))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"
+def evaluate(material, result):
+    if material.name == "foo":
+        result.add_warning("checked")
+
+def evaluate_all(materials, report):
+    names = [material.name for material in materials]
+    for material in materials:
+        if names.count(material.name) > 1:
+            report.add_warning(f"{material.name} is duplicated", material=material)
+    if len(materials) > 2:
+        report.add_error("too many materials")
+"#;
+
+    fn material(name: &str) -> Material {
+        Material {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn hatto_module(project_license: Option<&str>) -> HattoModule {
+        let classifier = Classifier::new().unwrap();
+        HattoModule {
+            compatibility: Compatibility::new(classifier.clone()).unwrap(),
+            classifier,
+            project_license: project_license.map(|expression| LicenseExpression::parse(expression).unwrap()),
+            project: None,
+        }
+    }
+
+    #[test]
+    fn evaluate_all_materials() {
+        let module = hatto_module(None);
+        let materials = vec![material("foo"), material("bar"), material("bar")];
+
+        let (results, document) = evaluate_materials(POLICY, &materials, &module).unwrap();
+        assert_eq!(results[0].warnings, vec!["checked"]);
+        assert_eq!(results[1].warnings, vec!["bar is duplicated"]);
+        assert_eq!(results[2].warnings, vec!["bar is duplicated"]);
+        assert!(!document.success);
+        assert_eq!(document.errors, vec!["too many materials"]);
+
+        let (_, document) = evaluate_materials(POLICY, &materials[..1], &module).unwrap();
+        assert!(document.success);
+
+        assert!(evaluate_materials("x = 1", &materials, &module).is_err());
+    }
+
+    #[test]
+    fn is_compatible() {
+        let policy = r#"
+import hatto
+
+def evaluate(material, result):
+    if not hatto.is_compatible(material.license_expression, hatto.project_license):
+        result.add_error("incompatible")
+    if not hatto.is_compatible("MIT", "GPL-3.0-only"):
+        result.add_error("MIT is incompatible")
+"#;
+        let module = hatto_module(Some("Apache-2.0"));
+        let mut materials = vec![material("foo"), material("bar")];
+        materials[0].set_license_strings(vec!["MIT".to_string()]);
+        materials[1].set_license_strings(vec!["GPL-2.0-only".to_string()]);
+
+        let (results, _) = evaluate_materials(policy, &materials, &module).unwrap();
+        assert!(results[0].success);
+        assert_eq!(results[1].errors, vec!["incompatible"]);
+    }
+
+    #[test]
+    fn compare_license_expressions() {
+        let policy = r#"
+import hatto
+
+def evaluate(material, result):
+    a = hatto.LicenseExpression("MIT OR Apache-2.0")
+    b = hatto.LicenseExpression("MIT OR Apache-2.0")
+    if not a == b or a != b:
+        result.add_error("equal expressions are not equal")
+    if a == hatto.LicenseExpression("MIT") or not a != hatto.LicenseExpression("MIT"):
+        result.add_error("different expressions are equal")
+    if a == "MIT OR Apache-2.0":
+        result.add_error("expression is equal to string")
+"#;
+        let module = hatto_module(None);
+
+        let (results, _) = evaluate_materials(policy, &[material("foo")], &module).unwrap();
+        assert_eq!(results[0].errors, Vec::<String>::new());
+    }
+
+    #[test]
+    fn curate_licenses() {
+        let curation = r#"
+def curate_material(material):
+    material.licenses = ["MIT OR Apache-2.0"]
+"#;
+        let module = hatto_module(None);
+
+        let curated = curate_materials(curation, vec![material("foo")], &module).unwrap();
+        assert_eq!(curated[0].licenses, vec!["MIT", "Apache-2.0"]);
+        assert_eq!(curated[0].license_expression.as_ref().unwrap().to_string(), "MIT OR Apache-2.0");
+    }
+}
@@ -0,0 +1,53 @@
+mod rdf;
+mod spdx3;
+mod xlsx;
+mod yaml;
+
+pub use rdf::load_rdf_file;
+pub use spdx3::{is_spdx3_context, load_spdx3_json_file};
+pub use xlsx::load_xlsx_file;
+pub use yaml::load_yaml_file;
+
+use serde::de::DeserializeOwned;
+
+/// Parse an SPDX enum value written in any case, e.g. `dependsOn`, `DEPENDS_ON` or `PACKAGE-MANAGER`.
+fn parse_enum<T: DeserializeOwned>(value: &str) -> Option<T> {
+    let value = value.trim();
+    let is_camel_case = value.chars().any(|c| c.is_ascii_lowercase());
+
+    let mut name = String::new();
+    for c in value.chars() {
+        if is_camel_case && c.is_ascii_uppercase() && !name.is_empty() {
+            name.push('_');
+        }
+        match c {
+            '-' | ' ' => name.push('_'),
+            c => name.push(c.to_ascii_uppercase()),
+        }
+    }
+
+    serde_json::from_value(serde_json::Value::String(name)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spdx_rs::models::{ExternalPackageReferenceCategory, RelationshipType};
+
+    #[test]
+    fn parse_spdx_enum() {
+        assert_eq!(
+            parse_enum::<RelationshipType>("dependsOn"),
+            Some(RelationshipType::DependsOn)
+        );
+        assert_eq!(
+            parse_enum::<RelationshipType>("DEPENDENCY_OF"),
+            Some(RelationshipType::DependencyOf)
+        );
+        assert_eq!(
+            parse_enum::<ExternalPackageReferenceCategory>("PACKAGE-MANAGER"),
+            Some(ExternalPackageReferenceCategory::PackageManager)
+        );
+        assert_eq!(parse_enum::<RelationshipType>("dependsOnFoo"), None);
+    }
+}
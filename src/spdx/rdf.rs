@@ -0,0 +1,366 @@
+use crate::error::Error;
+use crate::license::list::license_list;
+use crate::spdx::parse_enum;
+
+use minidom::Element;
+use spdx_rs::models::{
+    ExternalPackageReference, PackageInformation, Relationship, SpdxExpression, SPDX,
+};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+
+const RDF_NS: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const SPDX_NS: &str = "http://spdx.org/rdf/terms#";
+
+/// Load SPDX 2.x RDF/XML. Elements that hatto does not use are ignored.
+pub fn load_rdf_file(source: &File) -> Result<SPDX, Error> {
+    let reader = BufReader::new(source);
+    let element = Element::from_reader(reader)?;
+    if !element.is("RDF", RDF_NS) {
+        return Err(Error::Failure(
+            "SPDX RDF/XML root element must be rdf:RDF".to_string(),
+        ));
+    }
+
+    let mut spdx = SPDX::new("");
+    let mut package_ids: HashSet<String> = HashSet::new();
+    collect_elements(&element, None, &mut spdx, &mut package_ids)?;
+
+    Ok(spdx)
+}
+
+/// Walk all nodes, because packages and relationships can be nested anywhere in RDF/XML.
+fn collect_elements(
+    element: &Element,
+    subject: Option<&str>,
+    spdx: &mut SPDX,
+    package_ids: &mut HashSet<String>,
+) -> Result<(), Error> {
+    let about = rdf_attr(element, "about").map(spdx_id);
+    let subject = about.as_deref().or(subject);
+
+    if element.has_ns(SPDX_NS) {
+        match element.name() {
+            "SpdxDocument" => extract_document(element, spdx),
+            // a node without properties is a reference to the package defined elsewhere
+            "Package" if element.children().next().is_some() => {
+                if let Some(id) = &about {
+                    if package_ids.insert(id.clone()) {
+                        spdx.package_information.push(extract_package(element, id)?);
+                    }
+                }
+            }
+            "relationship" => {
+                if let Some(subject) = subject {
+                    for relationship in element
+                        .children()
+                        .filter(|child| child.is("Relationship", SPDX_NS))
+                    {
+                        if let Some(relationship) = extract_relationship(relationship, subject) {
+                            spdx.relationships.push(relationship);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    for child in element.children() {
+        collect_elements(child, subject, spdx, package_ids)?;
+    }
+
+    Ok(())
+}
+
+fn extract_document(element: &Element, spdx: &mut SPDX) {
+    let document = &mut spdx.document_creation_information;
+    if let Some(about) = rdf_attr(element, "about") {
+        document.spdx_identifier = spdx_id(about);
+        if let Some((namespace, _)) = about.split_once('#') {
+            document.spdx_document_namespace = namespace.to_string();
+        }
+    }
+
+    for child in element.children().filter(|child| child.has_ns(SPDX_NS)) {
+        match child.name() {
+            "specVersion" => document.spdx_version = rdf_value(child),
+            "dataLicense" => document.data_license = rdf_value(child),
+            "name" => document.document_name = rdf_value(child),
+            "describesPackage" => {
+                if let Some(id) = rdf_reference(child) {
+                    document.document_describes.push(id);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn extract_package(element: &Element, id: &str) -> Result<PackageInformation, Error> {
+    let mut package = PackageInformation {
+        package_spdx_identifier: id.to_string(),
+        ..Default::default()
+    };
+
+    for child in element.children().filter(|child| child.has_ns(SPDX_NS)) {
+        match child.name() {
+            "name" => package.package_name = rdf_value(child),
+            "versionInfo" => package.package_version = Some(rdf_value(child)),
+            "packageFileName" => package.package_file_name = Some(rdf_value(child)),
+            "supplier" => package.package_supplier = Some(rdf_value(child)),
+            "originator" => package.package_originator = Some(rdf_value(child)),
+            "downloadLocation" => package.package_download_location = rdf_value(child),
+            "homepage" => package.package_home_page = Some(rdf_value(child)),
+            "filesAnalyzed" => package.files_analyzed = rdf_value(child).parse().ok(),
+            "copyrightText" => package.copyright_text = Some(rdf_value(child)),
+            "licenseConcluded" => package.concluded_license = rdf_license_expression(child)?,
+            "licenseDeclared" => package.declared_license = rdf_license_expression(child)?,
+            "licenseInfoFromFiles" => {
+                if let Some(license) = rdf_license(child) {
+                    package.all_licenses_information_from_files.push(license);
+                }
+            }
+            "externalRef" => {
+                if let Some(reference) = child
+                    .children()
+                    .find(|c| c.is("ExternalRef", SPDX_NS))
+                    .and_then(extract_external_reference)
+                {
+                    package.external_reference.push(reference);
+                }
+            }
+            "primaryPackagePurpose" => {
+                let purpose = rdf_value(child);
+                package.primary_package_purpose =
+                    parse_enum(purpose.trim_start_matches("purpose_"));
+            }
+            _ => (),
+        }
+    }
+
+    Ok(package)
+}
+
+fn extract_external_reference(element: &Element) -> Option<ExternalPackageReference> {
+    let mut category = None;
+    let mut reference_type = None;
+    let mut locator = None;
+
+    for child in element.children().filter(|child| child.has_ns(SPDX_NS)) {
+        match child.name() {
+            "referenceCategory" => {
+                category = parse_enum(rdf_value(child).trim_start_matches("referenceCategory_"))
+            }
+            "referenceType" => reference_type = Some(rdf_value(child)),
+            "referenceLocator" => locator = Some(rdf_value(child)),
+            _ => (),
+        }
+    }
+
+    Some(ExternalPackageReference::new(
+        category?,
+        reference_type?,
+        locator?,
+        None,
+    ))
+}
+
+fn extract_relationship(element: &Element, subject: &str) -> Option<Relationship> {
+    let mut relationship_type = None;
+    let mut related = None;
+
+    for child in element.children().filter(|child| child.has_ns(SPDX_NS)) {
+        match child.name() {
+            "relationshipType" => {
+                relationship_type =
+                    parse_enum(rdf_value(child).trim_start_matches("relationshipType_"))
+            }
+            "relatedSpdxElement" => related = rdf_reference(child),
+            _ => (),
+        }
+    }
+
+    Some(Relationship::new(
+        subject,
+        &related?,
+        relationship_type?,
+        None,
+    ))
+}
+
+/// Attribute of rdf namespace. minidom keeps the prefix of attributes as written, so the prefix is ignored.
+fn rdf_attr<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+    element
+        .attrs()
+        .find(|(key, _)| *key == name || key.rsplit_once(':').map(|(_, local)| local) == Some(name))
+        .map(|(_, value)| value)
+}
+
+/// `https://example.com/doc#SPDXRef-foo` to `SPDXRef-foo`.
+fn spdx_id(uri: &str) -> String {
+    match uri.rsplit_once('#') {
+        Some((_, id)) => id.to_string(),
+        None => uri.to_string(),
+    }
+}
+
+/// Text of property, or the name of `rdf:resource` (e.g. `http://spdx.org/rdf/terms#noassertion` to `NOASSERTION`).
+fn rdf_value(element: &Element) -> String {
+    match rdf_attr(element, "resource") {
+        Some(resource) => resource_name(resource),
+        None => element.text().trim().to_string(),
+    }
+}
+
+fn resource_name(resource: &str) -> String {
+    let name = match resource.rsplit_once('#') {
+        Some((_, name)) => name,
+        None => resource.rsplit('/').next().unwrap_or(resource),
+    };
+
+    match name {
+        "noassertion" | "none" => name.to_uppercase(),
+        name => name.to_string(),
+    }
+}
+
+/// SPDX id of the element referred by `rdf:resource` or nested node.
+fn rdf_reference(element: &Element) -> Option<String> {
+    rdf_attr(element, "resource")
+        .or_else(|| {
+            element
+                .children()
+                .find_map(|child| rdf_attr(child, "about"))
+        })
+        .map(spdx_id)
+}
+
+fn rdf_license_expression(element: &Element) -> Result<Option<SpdxExpression>, Error> {
+    match rdf_license(element) {
+        Some(license) => Ok(Some(SpdxExpression::parse(&license)?)),
+        None => Ok(None),
+    }
+}
+
+/// License property to SPDX license expression.
+fn rdf_license(element: &Element) -> Option<String> {
+    match rdf_attr(element, "resource") {
+        Some(resource) => Some(resource_name(resource)),
+        None => element
+            .children()
+            .next()
+            .and_then(license_node),
+    }
+}
+
+fn license_node(node: &Element) -> Option<String> {
+    let members = || {
+        node.children()
+            .filter(|child| child.is("member", SPDX_NS))
+            .filter_map(|member| rdf_license(member).map(|license| (member, license)))
+    };
+
+    match node.name() {
+        "DisjunctiveLicenseSet" | "ConjunctiveLicenseSet" => {
+            let operator = match node.name() {
+                "DisjunctiveLicenseSet" => " OR ",
+                _ => " AND ",
+            };
+            let licenses = members()
+                .map(|(member, license)| match member.children().next() {
+                    Some(child) if child.name().ends_with("LicenseSet") => format!("({})", license),
+                    _ => license,
+                })
+                .collect::<Vec<_>>();
+            Some(licenses.join(operator))
+        }
+        "OrLaterOperator" => members().next().map(|(_, license)| or_later(&license)),
+        "WithExceptionOperator" => {
+            let (_, license) = members().next()?;
+            let exception = node
+                .get_child("licenseException", SPDX_NS)
+                .and_then(|exception| {
+                    rdf_attr(exception, "resource")
+                        .map(resource_name)
+                        .or_else(|| {
+                            exception
+                                .children()
+                                .find_map(|e| e.get_child("licenseExceptionId", SPDX_NS))
+                                .map(|id| id.text().trim().to_string())
+                        })
+                })?;
+            Some(format!("{} WITH {}", license, exception))
+        }
+        _ => match node.get_child("licenseId", SPDX_NS) {
+            Some(license_id) => Some(license_id.text().trim().to_string()),
+            None => rdf_attr(node, "about").map(resource_name),
+        },
+    }
+}
+
+/// `X-only` is `X-or-later` rather than `X-only+`, which means nothing. Others are `X+`.
+fn or_later(license: &str) -> String {
+    match license.strip_suffix("-only") {
+        Some(base) if license_list().get(&format!("{}-or-later", base)).is_some() => format!("{}-or-later", base),
+        _ => format!("{}+", license),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn license_sets() {
+        let element: Element = r#"<spdx:licenseConcluded xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:spdx="http://spdx.org/rdf/terms#">
+  <spdx:ConjunctiveLicenseSet>
+    <spdx:member rdf:resource="http://spdx.org/licenses/MIT"/>
+    <spdx:member>
+      <spdx:DisjunctiveLicenseSet>
+        <spdx:member>
+          <spdx:OrLaterOperator>
+            <spdx:member rdf:resource="http://spdx.org/licenses/GPL-2.0-only"/>
+          </spdx:OrLaterOperator>
+        </spdx:member>
+        <spdx:member>
+          <spdx:ExtractedLicensingInfo rdf:about="https://example.com/doc#LicenseRef-foo">
+            <spdx:licenseId>LicenseRef-foo</spdx:licenseId>
+          </spdx:ExtractedLicensingInfo>
+        </spdx:member>
+      </spdx:DisjunctiveLicenseSet>
+    </spdx:member>
+    <spdx:member>
+      <spdx:WithExceptionOperator>
+        <spdx:member rdf:resource="http://spdx.org/licenses/GPL-3.0-only"/>
+        <spdx:licenseException>
+          <spdx:LicenseException>
+            <spdx:licenseExceptionId>GCC-exception-3.1</spdx:licenseExceptionId>
+          </spdx:LicenseException>
+        </spdx:licenseException>
+      </spdx:WithExceptionOperator>
+    </spdx:member>
+  </spdx:ConjunctiveLicenseSet>
+</spdx:licenseConcluded>"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            rdf_license(&element),
+            Some(
+                "MIT AND (GPL-2.0-or-later OR LicenseRef-foo) AND GPL-3.0-only WITH GCC-exception-3.1"
+                    .to_string()
+            )
+        );
+        assert!(rdf_license_expression(&element).is_ok());
+    }
+
+    #[test]
+    fn or_later_operator() {
+        assert_eq!(or_later("GPL-2.0-only"), "GPL-2.0-or-later");
+        assert_eq!(or_later("LGPL-2.1-only"), "LGPL-2.1-or-later");
+        assert_eq!(or_later("GPL-2.0"), "GPL-2.0+");
+        assert_eq!(or_later("Apache-2.0"), "Apache-2.0+");
+    }
+}
@@ -0,0 +1,335 @@
+use crate::error::Error;
+use crate::spdx::parse_enum;
+
+use serde::Deserialize;
+use serde_json::Value;
+use spdx_rs::models::{
+    ExternalPackageReference, ExternalPackageReferenceCategory, PackageInformation, Relationship,
+    RelationshipType, SpdxExpression, SPDX,
+};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+/// `@context` of SPDX 3 documents, e.g. `https://spdx.org/rdf/3.0.1/spdx-context.jsonld`.
+const SPDX3_CONTEXT: &str = "spdx.org/rdf/3.";
+
+#[derive(Deserialize)]
+struct JsonLd {
+    #[serde(rename = "@context")]
+    context: Option<Value>,
+    #[serde(rename = "@graph", default)]
+    graph: Vec<Value>,
+}
+
+/// Whether `@context` of JSON-LD is SPDX 3.
+pub fn is_spdx3_context(context: &Value) -> bool {
+    match context {
+        Value::String(context) => context.contains(SPDX3_CONTEXT),
+        Value::Array(contexts) => contexts.iter().any(is_spdx3_context),
+        _ => false,
+    }
+}
+
+/// Load SPDX 3 JSON-LD as SPDX 2 model. `software_Package` elements become packages, license relationships
+/// become concluded and declared licenses, and other relationships are kept if SPDX 2 has the same type.
+pub fn load_spdx3_json_file(source: &File) -> Result<SPDX, Error> {
+    let json: JsonLd = serde_json::from_reader(BufReader::new(source))?;
+    if !json.context.as_ref().map(is_spdx3_context).unwrap_or(false) {
+        return Err(Error::Failure("@context is not SPDX 3".to_string()));
+    }
+
+    let graph = Graph::new(&json.graph);
+    let mut spdx = SPDX::new("");
+
+    for element in &json.graph {
+        match element_type(element) {
+            Some("SpdxDocument") => {
+                let document = &mut spdx.document_creation_information;
+                document.spdx_version = "SPDX-3.0".to_string();
+                document.spdx_identifier = element_id(element).unwrap_or_default().to_string();
+                document.document_name = string_property(element, "name").unwrap_or_default();
+                document
+                    .document_describes
+                    .extend(strings_property(element, "rootElement"));
+            }
+            Some("software_Sbom") | Some("Bundle") | Some("Bom") => {
+                let describes = &mut spdx.document_creation_information.document_describes;
+                describes.extend(strings_property(element, "rootElement"));
+            }
+            Some("software_Package") => spdx.package_information.push(graph.package(element)),
+            _ => (),
+        }
+    }
+
+    for element in &json.graph {
+        if !matches!(
+            element_type(element),
+            Some("Relationship") | Some("LifecycleScopedRelationship")
+        ) {
+            continue;
+        }
+
+        let from = match string_property(element, "from") {
+            Some(from) => from,
+            None => continue,
+        };
+        let relationship_type = string_property(element, "relationshipType").unwrap_or_default();
+        let to = strings_property(element, "to");
+
+        match relationship_type.as_str() {
+            "hasConcludedLicense" | "hasDeclaredLicense" => {
+                let package = match spdx
+                    .package_information
+                    .iter_mut()
+                    .find(|package| package.package_spdx_identifier == from)
+                {
+                    Some(package) => package,
+                    None => continue,
+                };
+                let licenses = to
+                    .iter()
+                    .filter_map(|id| graph.license(id))
+                    .collect::<Vec<_>>();
+                if licenses.is_empty() {
+                    continue;
+                }
+                let expression = match licenses.len() {
+                    1 => SpdxExpression::parse(&licenses[0])?,
+                    _ => SpdxExpression::parse(&join_licenses(&licenses, " AND "))?,
+                };
+                match relationship_type.as_str() {
+                    "hasConcludedLicense" => package.concluded_license = Some(expression),
+                    _ => package.declared_license = Some(expression),
+                }
+            }
+            relationship_type => {
+                if let Some(relationship_type) = parse_enum::<RelationshipType>(relationship_type) {
+                    for to in &to {
+                        spdx.relationships.push(Relationship::new(
+                            &from,
+                            to,
+                            relationship_type.clone(),
+                            None,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(spdx)
+}
+
+struct Graph<'a> {
+    elements: HashMap<&'a str, &'a Value>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(graph: &'a [Value]) -> Self {
+        let elements = graph
+            .iter()
+            .filter_map(|element| element_id(element).map(|id| (id, element)))
+            .collect();
+
+        Graph { elements }
+    }
+
+    fn package(&self, element: &Value) -> PackageInformation {
+        let purl = string_property(element, "software_packageUrl").or_else(|| {
+            element
+                .get("externalIdentifier")
+                .and_then(|identifiers| identifiers.as_array())
+                .into_iter()
+                .flatten()
+                .find(|identifier| {
+                    string_property(identifier, "externalIdentifierType").as_deref()
+                        == Some("packageUrl")
+                })
+                .and_then(|identifier| string_property(identifier, "identifier"))
+        });
+
+        PackageInformation {
+            package_name: string_property(element, "name")
+                .unwrap_or_else(|| "NOASSERTION".to_string()),
+            package_spdx_identifier: element_id(element).unwrap_or_default().to_string(),
+            package_version: string_property(element, "software_packageVersion"),
+            package_supplier: string_property(element, "suppliedBy")
+                .map(|agent| self.agent(&agent)),
+            package_originator: strings_property(element, "originatedBy")
+                .first()
+                .map(|agent| self.agent(agent)),
+            package_download_location: string_property(element, "software_downloadLocation")
+                .unwrap_or_else(|| "NOASSERTION".to_string()),
+            package_home_page: string_property(element, "software_homePage"),
+            copyright_text: string_property(element, "software_copyrightText"),
+            primary_package_purpose: string_property(element, "software_primaryPurpose")
+                .and_then(|purpose| parse_enum(&purpose)),
+            external_reference: purl
+                .map(|purl| {
+                    vec![ExternalPackageReference::new(
+                        ExternalPackageReferenceCategory::PackageManager,
+                        "purl".to_string(),
+                        purl,
+                        None,
+                    )]
+                })
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// Agent in SPDX 2 style, e.g. `Organization: heriet`.
+    fn agent(&self, id: &str) -> String {
+        let element = match self.elements.get(id) {
+            Some(element) => element,
+            None => return id.to_string(),
+        };
+        let name = string_property(element, "name").unwrap_or_else(|| id.to_string());
+
+        match element_type(element) {
+            Some("Organization") => format!("Organization: {}", name),
+            Some("Person") => format!("Person: {}", name),
+            Some("SoftwareAgent") => format!("Tool: {}", name),
+            _ => name,
+        }
+    }
+
+    /// SPDX license expression of license element or license IRI.
+    fn license(&self, id: &str) -> Option<String> {
+        let element = match self.elements.get(id) {
+            Some(element) => element,
+            None => return Some(license_name(id)),
+        };
+
+        match element_type(element)? {
+            "simplelicensing_LicenseExpression" => {
+                string_property(element, "simplelicensing_licenseExpression")
+            }
+            set @ ("expandedlicensing_DisjunctiveLicenseSet"
+            | "expandedlicensing_ConjunctiveLicenseSet") => {
+                let operator = match set {
+                    "expandedlicensing_DisjunctiveLicenseSet" => " OR ",
+                    _ => " AND ",
+                };
+                let members = strings_property(element, "expandedlicensing_member")
+                    .iter()
+                    .filter_map(|member| self.license(member))
+                    .collect::<Vec<_>>();
+                Some(join_licenses(&members, operator))
+            }
+            "expandedlicensing_OrLaterOperator" => {
+                let license = string_property(element, "expandedlicensing_subjectLicense")?;
+                Some(format!("{}+", self.license(&license)?))
+            }
+            "expandedlicensing_WithAdditionOperator" => {
+                let license =
+                    string_property(element, "expandedlicensing_subjectExtendableLicense")?;
+                let addition = string_property(element, "expandedlicensing_subjectAddition")?;
+                Some(format!(
+                    "{} WITH {}",
+                    self.license(&license)?,
+                    license_name(&addition)
+                ))
+            }
+            _ => Some(license_name(id)),
+        }
+    }
+}
+
+/// Join licenses by the operator. Compound licenses are enclosed in parentheses.
+fn join_licenses(licenses: &[String], operator: &str) -> String {
+    licenses
+        .iter()
+        .map(|license| match license.contains(' ') {
+            true => format!("({})", license),
+            false => license.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(operator)
+}
+
+/// `https://spdx.org/licenses/MIT` to `MIT`, `https://example.com/doc#LicenseRef-foo` to `LicenseRef-foo`.
+fn license_name(id: &str) -> String {
+    let name = match id.rsplit_once('#') {
+        Some((_, name)) => name,
+        None => id.rsplit('/').next().unwrap_or(id),
+    };
+
+    match name {
+        "NoAssertionLicense" | "expandedlicensing_NoAssertionLicense" => "NOASSERTION".to_string(),
+        "NoneLicense" | "expandedlicensing_NoneLicense" => "NONE".to_string(),
+        name => name.to_string(),
+    }
+}
+
+fn element_id(element: &Value) -> Option<&str> {
+    element
+        .get("spdxId")
+        .or_else(|| element.get("@id"))
+        .and_then(|id| id.as_str())
+}
+
+fn element_type(element: &Value) -> Option<&str> {
+    element
+        .get("type")
+        .or_else(|| element.get("@type"))
+        .and_then(|element_type| element_type.as_str())
+}
+
+/// String property, or id of the nested element.
+fn string_property(element: &Value, name: &str) -> Option<String> {
+    match element.get(name)? {
+        Value::String(value) => Some(value.clone()),
+        value @ Value::Object(_) => element_id(value).map(|id| id.to_string()),
+        _ => None,
+    }
+}
+
+fn strings_property(element: &Value, name: &str) -> Vec<String> {
+    match element.get(name) {
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|value| match value {
+                Value::String(value) => Some(value.clone()),
+                value => element_id(value).map(|id| id.to_string()),
+            })
+            .collect(),
+        Some(_) => string_property(element, name).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detect_spdx3_context() {
+        assert!(is_spdx3_context(&json!("https://spdx.org/rdf/3.0.1/spdx-context.jsonld")));
+        assert!(is_spdx3_context(&json!(["https://spdx.org/rdf/3.0.0/spdx-context.jsonld"])));
+        assert!(!is_spdx3_context(&json!("https://schema.org/")));
+        assert!(!is_spdx3_context(&json!({"spdx": "https://spdx.org/rdf/3.0.1/terms/"})));
+    }
+
+    #[test]
+    fn load_licenses() {
+        let file = File::open("test/spdx/basic.spdx3.json").unwrap();
+        let spdx = load_spdx3_json_file(&file).unwrap();
+
+        let foo = &spdx.package_information[0];
+        assert_eq!(foo.concluded_license.as_ref().unwrap().to_string(), "MIT");
+        assert_eq!(foo.declared_license.as_ref().unwrap().to_string(), "MIT");
+
+        let bar = &spdx.package_information[1];
+        assert_eq!(
+            bar.concluded_license.as_ref().unwrap().to_string(),
+            "MIT OR Apache-2.0"
+        );
+        assert_eq!(
+            bar.declared_license.as_ref().unwrap().to_string(),
+            "MIT OR Apache-2.0"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::error::Error;
+use crate::spdx::parse_enum;
+
+use calamine::{Data, Range, Reader, Xlsx};
+use spdx_rs::models::{
+    ExternalPackageReference, PackageInformation, Relationship, SpdxExpression, SPDX,
+};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+const DOCUMENT_SHEET: &str = "Document Info";
+const PACKAGE_SHEET: &str = "Package Info";
+const EXTERNAL_REFS_SHEET: &str = "External Refs";
+const RELATIONSHIPS_SHEET: &str = "Relationships";
+
+/// Load SPDX 2.x spreadsheet. Each sheet has a header row, and columns are found by the header name.
+pub fn load_xlsx_file(source: &File) -> Result<SPDX, Error> {
+    let mut workbook = Xlsx::new(BufReader::new(source))?;
+    let mut spdx = SPDX::new("");
+
+    if let Some(sheet) = load_sheet(&mut workbook, DOCUMENT_SHEET)? {
+        let document = &mut spdx.document_creation_information;
+        // creators may continue on the next rows, but hatto does not use them
+        if let Some(row) = sheet.rows().next() {
+            document.spdx_version = row.get(&["SPDX Version"]).unwrap_or_default();
+            document.data_license = row.get(&["Data License"]).unwrap_or_default();
+            document.spdx_identifier = row.get(&["SPDX Identifier"]).unwrap_or_default();
+            document.document_name = row.get(&["Document Name"]).unwrap_or_default();
+            document.spdx_document_namespace = row.get(&["Document Namespace"]).unwrap_or_default();
+            document.document_describes =
+                row.get_list(&["Document Contents", "Document Describes"]);
+        }
+    }
+
+    let sheet = match load_sheet(&mut workbook, PACKAGE_SHEET)? {
+        Some(sheet) => sheet,
+        None => {
+            return Err(Error::Failure(format!(
+                "SPDX spreadsheet must have {} sheet",
+                PACKAGE_SHEET
+            )))
+        }
+    };
+    for row in sheet.rows() {
+        let (name, id) = match (row.get(&["Package Name"]), row.get(&["SPDX Identifier"])) {
+            (Some(name), Some(id)) => (name, id),
+            _ => continue,
+        };
+
+        spdx.package_information.push(PackageInformation {
+            package_name: name,
+            package_spdx_identifier: id,
+            package_version: row.get(&["Package Version"]),
+            package_file_name: row.get(&["Package FileName", "Package File Name"]),
+            package_supplier: row.get(&["Package Supplier"]),
+            package_originator: row.get(&["Package Originator"]),
+            package_download_location: row
+                .get(&["Package Download Location"])
+                .unwrap_or_else(|| "NOASSERTION".to_string()),
+            package_home_page: row.get(&["Home Page"]),
+            files_analyzed: row
+                .get(&["Files Analyzed"])
+                .and_then(|b| b.to_lowercase().parse().ok()),
+            concluded_license: row.get_expression(&["License Concluded"])?,
+            declared_license: row.get_expression(&["License Declared"])?,
+            all_licenses_information_from_files: row.get_list(&["License Info From Files"]),
+            copyright_text: row.get(&["Package Copyright Text", "Copyright Text"]),
+            primary_package_purpose: row
+                .get(&["Primary Package Purpose"])
+                .and_then(|p| parse_enum(&p)),
+            ..Default::default()
+        });
+    }
+
+    if let Some(sheet) = load_sheet(&mut workbook, EXTERNAL_REFS_SHEET)? {
+        for row in sheet.rows() {
+            let id = row.get(&["Package ID", "SPDX Identifier"]);
+            let category = row.get(&["Category"]).and_then(|c| parse_enum(&c));
+            let reference_type = row.get(&["Type"]);
+            let locator = row.get(&["Locator"]);
+
+            if let (Some(id), Some(category), Some(reference_type), Some(locator)) =
+                (id, category, reference_type, locator)
+            {
+                if let Some(package) = spdx
+                    .package_information
+                    .iter_mut()
+                    .find(|package| package.package_spdx_identifier == id)
+                {
+                    package
+                        .external_reference
+                        .push(ExternalPackageReference::new(
+                            category,
+                            reference_type,
+                            locator,
+                            row.get(&["Comment"]),
+                        ));
+                }
+            }
+        }
+    }
+
+    if let Some(sheet) = load_sheet(&mut workbook, RELATIONSHIPS_SHEET)? {
+        for row in sheet.rows() {
+            let element = row.get(&["SPDX Element ID", "SPDX Identifier A", "SPDX Identifier"]);
+            let relationship_type = row
+                .get(&["Relationship", "Relationship Type"])
+                .and_then(|r| parse_enum(&r));
+            let related = row.get(&["Related SPDX Element", "SPDX Identifier B"]);
+
+            if let (Some(element), Some(relationship_type), Some(related)) =
+                (element, relationship_type, related)
+            {
+                spdx.relationships.push(Relationship::new(
+                    &element,
+                    &related,
+                    relationship_type,
+                    row.get(&["Relationship Comment", "Comment"]),
+                ));
+            }
+        }
+    }
+
+    Ok(spdx)
+}
+
+fn load_sheet(workbook: &mut Xlsx<BufReader<&File>>, name: &str) -> Result<Option<Sheet>, Error> {
+    if !workbook
+        .sheet_names()
+        .iter()
+        .any(|sheet_name| sheet_name == name)
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(Sheet::new(workbook.worksheet_range(name)?)))
+}
+
+struct Sheet {
+    range: Range<Data>,
+    columns: HashMap<String, usize>,
+}
+
+impl Sheet {
+    fn new(range: Range<Data>) -> Self {
+        let columns = range
+            .rows()
+            .next()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, header)| (header_key(&header.to_string()), i))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Sheet { range, columns }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+        self.range
+            .rows()
+            .skip(1)
+            .map(|cells| Row { sheet: self, cells })
+    }
+}
+
+struct Row<'a> {
+    sheet: &'a Sheet,
+    cells: &'a [Data],
+}
+
+impl<'a> Row<'a> {
+    /// Value of the first column found by the header names. Empty cell is `None`.
+    fn get(&self, headers: &[&str]) -> Option<String> {
+        let column = headers
+            .iter()
+            .find_map(|header| self.sheet.columns.get(&header_key(header)))?;
+        let value = self.cells.get(*column)?.to_string();
+
+        match value.trim() {
+            "" => None,
+            value => Some(value.to_string()),
+        }
+    }
+
+    /// Comma separated values.
+    fn get_list(&self, headers: &[&str]) -> Vec<String> {
+        self.get(headers)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_expression(&self, headers: &[&str]) -> Result<Option<SpdxExpression>, Error> {
+        match self.get(headers) {
+            Some(expression) => Ok(Some(SpdxExpression::parse(&expression)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// `Package Name` and `package name` are the same header.
+fn header_key(header: &str) -> String {
+    header
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
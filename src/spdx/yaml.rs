@@ -0,0 +1,48 @@
+use crate::error::Error;
+
+use spdx_rs::models::SPDX;
+use std::fs::File;
+use std::io::BufReader;
+
+/// Load SPDX 2.2/2.3 YAML. JSON is also loaded because JSON is YAML.
+pub fn load_yaml_file(source: &File) -> Result<SPDX, Error> {
+    let mut value: serde_yaml::Value = serde_yaml::from_reader(BufReader::new(source))?;
+    // merge keys (`<<: *anchor`) are not applied by deserialization
+    value.apply_merge()?;
+    let spdx = serde_yaml::from_value(value)?;
+
+    Ok(spdx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_scalars_and_anchors() {
+        let file = File::open("test/spdx/basic.spdx.yaml").unwrap();
+        let spdx = load_yaml_file(&file).unwrap();
+
+        assert_eq!(
+            spdx.document_creation_information.document_comment.as_deref(),
+            Some("This document describes foo and its dependency bar.\nIt is a fixture of hatto.\n")
+        );
+
+        let foo = &spdx.package_information[0];
+        assert_eq!(foo.package_originator, foo.package_supplier);
+        assert_eq!(
+            foo.comments_on_license.as_deref(),
+            Some("The license is concluded from the LICENSE file of the repository.\n")
+        );
+        assert_eq!(
+            foo.copyright_text.as_deref(),
+            Some("Copyright (c) 2022 heriet\nCopyright (c) 2022 contributors")
+        );
+
+        let bar = &spdx.package_information[1];
+        assert_eq!(bar.package_download_location, "NOASSERTION");
+        assert_eq!(bar.files_analyzed, Some(false));
+        assert_eq!(bar.comments_on_license.as_deref(), Some("Dual licensed.\n\nSee README.\n"));
+        assert!(bar.external_reference.is_empty());
+    }
+}